@@ -2,8 +2,11 @@
 
 from __future__ import annotations
 
+import secrets
 from functools import lru_cache
 
+from fastapi import Depends, Header, HTTPException, status
+
 from context_cache.core.config import Settings, get_settings
 from context_cache.db.sqlite import SQLiteDatabase
 from context_cache.ingest.embeddings import EmbeddingModel
@@ -22,6 +25,23 @@ def get_app_settings() -> Settings:
     return get_settings()
 
 
+def require_api_token(
+    authorization: str | None = Header(default=None),
+    settings: Settings = Depends(get_app_settings),
+) -> None:
+    """Reject requests without the configured bearer token; open when none is configured."""
+    expected = settings.api_token
+    if not expected:
+        return
+    scheme, _, token = (authorization or "").partition(" ")
+    if scheme.lower() != "bearer" or not secrets.compare_digest(token.strip(), expected):
+        raise HTTPException(
+            status_code=status.HTTP_401_UNAUTHORIZED,
+            detail="Missing or invalid API token",
+            headers={"WWW-Authenticate": "Bearer"},
+        )
+
+
 def get_database() -> SQLiteDatabase:
     global _DB
     if _DB is None:
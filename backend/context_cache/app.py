@@ -2,7 +2,7 @@
 
 from __future__ import annotations
 
-from fastapi import FastAPI
+from fastapi import Depends, FastAPI
 from fastapi.middleware.cors import CORSMiddleware
 
 from context_cache.api.dependencies import (
@@ -12,6 +12,7 @@ from context_cache.api.dependencies import (
     get_ingest_pipeline,
     get_query_service,
     get_vector_index,
+    require_api_token,
 )
 from context_cache.api.routes_admin import router as admin_router
 from context_cache.api.routes_ingest import router as ingest_router
@@ -39,9 +40,11 @@ app.add_middleware(
     allow_headers=['*'],
 )
 
-app.include_router(ingest_router, prefix="/ingest", tags=["ingest"])
-app.include_router(query_router, prefix="", tags=["query"])
-app.include_router(admin_router, prefix="", tags=["admin"])
+# `/health` stays open so supervisors can probe liveness without the token.
+authenticated = [Depends(require_api_token)]
+app.include_router(ingest_router, prefix="/ingest", tags=["ingest"], dependencies=authenticated)
+app.include_router(query_router, prefix="", tags=["query"], dependencies=authenticated)
+app.include_router(admin_router, prefix="", tags=["admin"], dependencies=authenticated)
 
 
 @app.on_event("startup")
@@ -29,6 +29,9 @@ def _resolve_host(override: Optional[str]) -> str:
 def _request(method: str, path: str, host: Optional[str] = None, **kwargs) -> requests.Response:
     base = _resolve_host(host)
     url = f"{base}{path}"
+    token = os.environ.get("CTXC_API_TOKEN")
+    if token:
+        kwargs.setdefault("headers", {})["Authorization"] = f"Bearer {token}"
     resp = requests.request(method, url, timeout=60, **kwargs)
     if not resp.ok:
         try:
@@ -14,6 +14,7 @@ ENV_PREFIX = "CTXC_"
 DEFAULT_CONFIG_PATH = Path("~/.config/context-cache/config.yaml")
 
 _YAML_KEY_MAP: Mapping[tuple[str, ...], str] = {
+    ("server", "api_token"): "api_token",
     ("storage", "db_path"): "db_path",
     ("storage", "use_faiss"): "use_faiss",
     ("embeddings", "model"): "embedding_model",
@@ -40,6 +41,7 @@ class Settings(BaseModel):
     top_k_final: int = 8
     watch_include: str = "**/*.{md,txt,pdf,docx,eml,mbox}"
     watch_exclude: str = "**/{.git,.obsidian,node_modules}/**"
+    api_token: str | None = None
 
     model_config = {
         "validate_assignment": True,
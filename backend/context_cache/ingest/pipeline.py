@@ -280,6 +280,9 @@ class IngestPipeline:
         row = self.db.execute("SELECT id FROM sources WHERE uri = ?", [uri]).fetchone()
         if row:
             return row["id"]
+        enclosing = self._enclosing_folder_source(path)
+        if enclosing:
+            return enclosing
         now = now_ms()
         source_id = new_id("src")
         self.db.execute(
@@ -301,6 +304,13 @@ class IngestPipeline:
         self.db.commit()
         return source_id
 
+    def _enclosing_folder_source(self, path: Path) -> str | None:
+        """Return the closest registered folder source containing ``path``, if any."""
+        depths = {parent.as_uri(): depth for depth, parent in enumerate(path.parents)}
+        rows = self.db.query("SELECT id, uri FROM sources WHERE kind = 'folder'", [])
+        matches = [(depths[row["uri"]], row["id"]) for row in rows if row["uri"] in depths]
+        return min(matches)[1] if matches else None
+
 
     def _update_index_metric(self) -> None:
         try:
@@ -43,3 +43,41 @@ def test_ingest_and_query_flow(tmp_path: Path, client: TestClient) -> None:
     sources_resp = client.get("/sources")
     assert sources_resp.status_code == 200
     assert sources_resp.json()
+
+
+def test_ingest_path_reuses_enclosing_folder_source(tmp_path: Path, client: TestClient) -> None:
+    folder = tmp_path / "notes"
+    folder.mkdir()
+    note = folder / "note.md"
+    note.write_text("# Note\n\nKept under the registered folder.")
+
+    source_resp = client.post("/sources", json={"kind": "folder", "uri": str(folder), "label": "Notes"})
+    assert source_resp.status_code == 200
+    source_id = source_resp.json()["id"]
+
+    ingest_resp = client.post("/ingest", json={"paths": [str(note)]})
+    assert ingest_resp.status_code == 200
+    assert ingest_resp.json()["stats"]["processed"] == 1
+
+    sources = client.get("/sources").json()
+    assert [source["id"] for source in sources] == [source_id]
+
+
+def test_api_token_required_when_configured(monkeypatch: pytest.MonkeyPatch) -> None:
+    from context_cache.api import dependencies as deps
+    from context_cache.core import config
+
+    monkeypatch.setenv("CTXC_API_TOKEN", "secret-token")
+    config.get_settings.cache_clear()
+    deps.get_app_settings.cache_clear()
+    try:
+        with TestClient(app) as client:
+            assert client.get("/health").status_code == 200
+            assert client.get("/sources").status_code == 401
+            wrong = client.get("/sources", headers={"Authorization": "Bearer nope"})
+            assert wrong.status_code == 401
+            ok = client.get("/sources", headers={"Authorization": "Bearer secret-token"})
+            assert ok.status_code == 200
+    finally:
+        config.get_settings.cache_clear()
+        deps.get_app_settings.cache_clear()
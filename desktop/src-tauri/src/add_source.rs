@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use context_cache_desktop_lib::dto::{
+    IngestRequest, SourceCreateRequest, SourceKind, SourceResponse,
+};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::{ingest, sources};
+
+const WINDOW_LABEL: &str = "add-source";
+
+/// Folder chosen from the tray that the add-source form is editing.
+#[derive(Default)]
+pub struct PendingFolder(Mutex<Option<PathBuf>>);
+
+#[derive(Serialize, Clone)]
+pub struct SourceDraft {
+    uri: String,
+    label: String,
+}
+
+pub fn pick_folder(app: &AppHandle) {
+    let handle = app.clone();
+    app.dialog()
+        .file()
+        .set_title("Add Folder as Source")
+        .pick_folder(move |folder| {
+            let Some(path) = folder.and_then(|folder| folder.into_path().ok()) else {
+                return;
+            };
+            if let Err(err) =
+                sources::validate_source_path(SourceKind::Folder, &path.to_string_lossy())
+            {
+                show_error(&handle, &err);
+                return;
+            }
+            *handle.state::<PendingFolder>().0.lock().unwrap() = Some(path);
+            if let Err(err) = open_form(&handle) {
+                eprintln!("Failed to open add-source window: {err}");
+            }
+        });
+}
+
+fn open_form(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        window.reload()?;
+        window.show()?;
+        return window.set_focus();
+    }
+    WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App("add-source".into()))
+        .title("Add Folder as Source")
+        .inner_size(480.0, 380.0)
+        .resizable(false)
+        .build()?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_source_draft(pending: State<'_, PendingFolder>) -> Option<SourceDraft> {
+    let guard = pending.0.lock().unwrap();
+    guard.as_deref().map(|path| SourceDraft {
+        uri: path.to_string_lossy().into_owned(),
+        label: default_label(path),
+    })
+}
+
+#[tauri::command]
+pub async fn submit_source_draft(
+    app_handle: AppHandle,
+    pending: State<'_, PendingFolder>,
+    source: SourceCreateRequest,
+) -> Result<SourceResponse, String> {
+    let handle = app_handle.clone();
+    let created = crate::blocking(move || {
+        let created = sources::register_source(&handle, &crate::client(&handle), source)?;
+        sources::sources_changed(&handle);
+        Ok(created)
+    })
+    .await?;
+    pending.0.lock().unwrap().take();
+    if let Some(window) = app_handle.get_webview_window(WINDOW_LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    offer_ingest(&app_handle, &created);
+    Ok(created)
+}
+
+fn offer_ingest(app: &AppHandle, source: &SourceResponse) {
+    let label = source.label.clone().unwrap_or_else(|| source.uri.clone());
+    let handle = app.clone();
+    let source_id = source.id.clone();
+    app.dialog()
+        .message(format!("\"{label}\" was added. Ingest it now?"))
+        .title("Source Added")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Ingest Now".into(),
+            "Later".into(),
+        ))
+        .show(move |confirmed| {
+            if !confirmed {
+                return;
+            }
+            let request = IngestRequest {
+                sources: Some(vec![source_id]),
+                ..IngestRequest::default()
+            };
+            ingest::spawn_ingest(&handle, request);
+        });
+}
+
+fn show_error(app: &AppHandle, message: &str) {
+    app.dialog()
+        .message(message)
+        .title("Context Cache")
+        .kind(MessageDialogKind::Error)
+        .show(|_| {});
+}
+
+fn default_label(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
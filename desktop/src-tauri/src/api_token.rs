@@ -0,0 +1,67 @@
+//! API tokens kept in the OS keyring: the one shared by the shell and the local backend, and
+//! the tokens of backend profiles, which never go into the settings file.
+
+use keyring::Entry;
+use rand::RngCore;
+
+use crate::settings::APP_IDENTIFIER;
+
+const ACCOUNT: &str = "local-api-token";
+const PROFILE_ACCOUNT_PREFIX: &str = "profile-token:";
+const TOKEN_BYTES: usize = 32;
+
+/// Returns the stored token, generating and storing one on first use.
+pub fn local_token() -> Result<String, String> {
+    let entry = Entry::new(APP_IDENTIFIER, ACCOUNT)
+        .map_err(|err| format!("Cannot open the OS keyring: {err}"))?;
+    match entry.get_password() {
+        Ok(token) if !token.trim().is_empty() => Ok(token),
+        Ok(_) | Err(keyring::Error::NoEntry) => {
+            let token = generate();
+            entry
+                .set_password(&token)
+                .map_err(|err| format!("Cannot store the API token in the OS keyring: {err}"))?;
+            Ok(token)
+        }
+        Err(err) => Err(format!(
+            "Cannot read the API token from the OS keyring: {err}"
+        )),
+    }
+}
+
+/// The token stored for the backend profile `name`, if any.
+pub fn profile_token(name: &str) -> Result<Option<String>, String> {
+    match profile_entry(name)?.get_password() {
+        Ok(token) if !token.is_empty() => Ok(Some(token)),
+        Ok(_) | Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(format!(
+            "Cannot read the token of profile \"{name}\" from the OS keyring: {err}"
+        )),
+    }
+}
+
+/// Stores the token of profile `name`, or deletes it for `None`.
+pub fn set_profile_token(name: &str, token: Option<&str>) -> Result<(), String> {
+    let entry = profile_entry(name)?;
+    let result = match token.filter(|token| !token.is_empty()) {
+        Some(token) => entry.set_password(token),
+        None => match entry.delete_credential() {
+            Err(keyring::Error::NoEntry) => Ok(()),
+            result => result,
+        },
+    };
+    result.map_err(|err| {
+        format!("Cannot store the token of profile \"{name}\" in the OS keyring: {err}")
+    })
+}
+
+fn profile_entry(name: &str) -> Result<Entry, String> {
+    Entry::new(APP_IDENTIFIER, &format!("{PROFILE_ACCOUNT_PREFIX}{name}"))
+        .map_err(|err| format!("Cannot open the OS keyring: {err}"))
+}
+
+fn generate() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,410 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+
+use context_cache_desktop_lib::api_token;
+use context_cache_desktop_lib::client::BackendClient;
+use context_cache_desktop_lib::settings::{self, Settings};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::sidecar::Sidecar;
+use crate::{clippings, quick_search, shortcuts, sources, tray, watcher};
+
+/// Settings as stored on disk; environment overrides are applied on read.
+pub struct SettingsState {
+    path: PathBuf,
+    stored: Mutex<Settings>,
+    /// Profiles whose token stays in the settings file because the keyring would not take it.
+    plaintext_tokens: Mutex<BTreeSet<String>>,
+    /// Keyring token for the local backend; never written to the settings file.
+    local_token: Option<String>,
+}
+
+/// Token edits from the Settings page by profile name: `Some` sets a token, `None` clears it.
+///
+/// Profiles left out keep their token, which the webview never gets to see.
+pub type TokenChanges = BTreeMap<String, Option<String>>;
+
+/// What `get_settings` returns and `settings-changed` carries; profile tokens are left out.
+#[derive(Serialize, Clone)]
+pub struct SettingsView {
+    /// Values persisted in the settings file; this is what the UI edits.
+    settings: Settings,
+    /// Values in effect after environment overrides.
+    effective: Settings,
+    /// Profiles that have a token stored.
+    token_set: Vec<String>,
+    /// Dotted names of fields currently overridden by `CTXC_*` variables.
+    overridden: Vec<&'static str>,
+    /// URL the shell is currently talking to.
+    backend_url: String,
+    path: String,
+}
+
+impl SettingsState {
+    pub fn load() -> Self {
+        let path = settings::default_path();
+        let mut stored = Settings::load(&path).unwrap_or_else(|err| {
+            eprintln!("Failed to read {}: {err}; using defaults", path.display());
+            Settings::default()
+        });
+        let plaintext_tokens = move_tokens_to_keyring(&path, &stored);
+        for profile in &mut stored.profiles {
+            if profile.auth_token.is_none() {
+                profile.auth_token = api_token::profile_token(&profile.name)
+                    .map_err(|err| eprintln!("{err}"))
+                    .ok()
+                    .flatten();
+            }
+        }
+        let local_token = api_token::local_token()
+            .map_err(|err| eprintln!("{err}; the local backend will not require a token"))
+            .ok();
+        Self {
+            path,
+            stored: Mutex::new(stored),
+            plaintext_tokens: Mutex::new(plaintext_tokens),
+            local_token,
+        }
+    }
+
+    pub fn effective(&self) -> Settings {
+        let mut settings = self.stored.lock().unwrap().clone();
+        settings.apply_env_overrides();
+        if let Some(token) = &self.local_token {
+            settings.apply_local_token(token);
+        }
+        settings
+    }
+
+    fn view(&self, backend_url: String) -> SettingsView {
+        let mut settings = self.stored.lock().unwrap().clone();
+        let token_set = settings
+            .profiles
+            .iter()
+            .filter(|profile| profile.auth_token.is_some())
+            .map(|profile| profile.name.clone())
+            .collect();
+        let mut effective = settings.clone();
+        let overridden = effective.apply_env_overrides();
+        for profile in settings.profiles.iter_mut().chain(&mut effective.profiles) {
+            profile.auth_token = None;
+        }
+        SettingsView {
+            backend_url,
+            settings,
+            effective,
+            token_set,
+            overridden,
+            path: self.path.to_string_lossy().into_owned(),
+        }
+    }
+}
+
+/// Moves tokens found in the settings file, e.g. written by older versions, to the keyring.
+///
+/// Returns the profiles whose token the keyring would not take; the file keeps those.
+fn move_tokens_to_keyring(path: &Path, stored: &Settings) -> BTreeSet<String> {
+    let in_file: BTreeSet<String> = stored
+        .profiles
+        .iter()
+        .filter(|profile| profile.auth_token.is_some())
+        .map(|profile| profile.name.clone())
+        .collect();
+    let plaintext = store_tokens(stored, stored, &in_file, api_token::set_profile_token);
+    if plaintext != in_file {
+        if let Err(err) = stored.save(path, &plaintext) {
+            eprintln!("Failed to write {}: {err}", path.display());
+        }
+    }
+    plaintext
+}
+
+/// Brings the keyring in step with the profile tokens of `settings`, dropping removed profiles.
+///
+/// Tokens of the `plaintext` profiles are in the settings file and are offered to the keyring
+/// again. Returns the profiles whose token `put` could not store; they stay in the file, so
+/// nothing is lost on a machine without a working keyring.
+fn store_tokens(
+    previous: &Settings,
+    settings: &Settings,
+    plaintext: &BTreeSet<String>,
+    mut put: impl FnMut(&str, Option<&str>) -> Result<(), String>,
+) -> BTreeSet<String> {
+    let mut kept = BTreeSet::new();
+    for profile in &settings.profiles {
+        let unchanged =
+            previous.profile(&profile.name).map(|old| &old.auth_token) == Some(&profile.auth_token);
+        if unchanged && !plaintext.contains(&profile.name) {
+            continue;
+        }
+        if let Err(err) = put(&profile.name, profile.auth_token.as_deref()) {
+            if profile.auth_token.is_some() {
+                eprintln!("{err}; keeping it in the settings file");
+                kept.insert(profile.name.clone());
+            } else {
+                eprintln!("{err}");
+            }
+        }
+    }
+    for old in &previous.profiles {
+        if settings.profile(&old.name).is_none() && old.auth_token.is_some() {
+            if let Err(err) = put(&old.name, None) {
+                eprintln!("{err}");
+            }
+        }
+    }
+    kept
+}
+
+/// Gives the profiles of `settings`, which come from the webview without tokens, their stored
+/// token or the one in `changes`. A renamed profile keeps the token of the one it replaced.
+fn restore_tokens(previous: &Settings, settings: &mut Settings, changes: &TokenChanges) {
+    let names: Vec<String> = settings
+        .profiles
+        .iter()
+        .map(|profile| profile.name.clone())
+        .collect();
+    for (index, profile) in settings.profiles.iter_mut().enumerate() {
+        profile.auth_token = match changes.get(&profile.name) {
+            Some(token) => token.clone().filter(|token| !token.is_empty()),
+            None => previous
+                .profile(&profile.name)
+                .or_else(|| {
+                    previous
+                        .profiles
+                        .get(index)
+                        .filter(|old| !names.contains(&old.name))
+                })
+                .and_then(|old| old.auth_token.clone()),
+        };
+    }
+}
+
+/// Shorthand for the effective settings of a running app.
+pub fn current(app: &AppHandle) -> Settings {
+    app.state::<SettingsState>().effective()
+}
+
+/// Client for the active profile; a running sidecar keeps serving the local one until the next launch.
+pub fn client_for(app: &AppHandle, settings: &Settings) -> BackendClient {
+    let profile = settings.active();
+    let base_url = match app.try_state::<Sidecar>() {
+        Some(sidecar) if settings.active_is_local() => sidecar.base_url().to_string(),
+        _ => profile.base_url.clone(),
+    };
+    BackendClient::new(base_url)
+        .with_auth_token(profile.auth_token.clone())
+        .with_tls(&profile.tls)
+        .with_network(&settings.network)
+}
+
+#[derive(Serialize, Clone)]
+struct ProfileChanged {
+    name: String,
+    base_url: String,
+}
+
+#[tauri::command]
+pub fn get_settings(app_handle: AppHandle, state: State<'_, SettingsState>) -> SettingsView {
+    state.view(crate::client(&app_handle).base_url().to_string())
+}
+
+/// Persists new settings, points the shell at the resulting backend and notifies every window.
+///
+/// Tokens in `settings` are ignored; they only change through `tokens`. Sidecar changes take
+/// effect on the next launch.
+#[tauri::command]
+pub fn set_settings(
+    app_handle: AppHandle,
+    mut settings: Settings,
+    tokens: Option<TokenChanges>,
+) -> Result<SettingsView, String> {
+    let previous = app_handle
+        .state::<SettingsState>()
+        .stored
+        .lock()
+        .unwrap()
+        .clone();
+    restore_tokens(&previous, &mut settings, &tokens.unwrap_or_default());
+    store(&app_handle, settings)
+}
+
+#[tauri::command]
+pub fn switch_profile(app_handle: AppHandle, name: String) -> Result<SettingsView, String> {
+    let mut settings = app_handle
+        .state::<SettingsState>()
+        .stored
+        .lock()
+        .unwrap()
+        .clone();
+    if settings.profile(&name).is_none() {
+        return Err(format!("Unknown backend profile \"{name}\""));
+    }
+    settings.active_profile = name;
+    store(&app_handle, settings)
+}
+
+fn store(app: &AppHandle, mut settings: Settings) -> Result<SettingsView, String> {
+    settings.normalize();
+    settings.validate()?;
+    let quick_search = shortcuts::parse(&settings.quick_search.shortcut, "quick-search")?;
+    let clipping = shortcuts::parse(&settings.clippings.shortcut, "clipboard capture")?;
+    if quick_search.is_some() && quick_search == clipping {
+        return Err("Quick search and clipboard capture cannot share a shortcut".into());
+    }
+    let state = app.state::<SettingsState>();
+    let previous_profile = state.effective().active().name.clone();
+    let previous_url = crate::client(app).base_url().to_string();
+    let previous = state.stored.lock().unwrap().clone();
+    let plaintext = state.plaintext_tokens.lock().unwrap().clone();
+    let plaintext = store_tokens(
+        &previous,
+        &settings,
+        &plaintext,
+        api_token::set_profile_token,
+    );
+    settings
+        .save(&state.path, &plaintext)
+        .map_err(|err| format!("Failed to write {}: {err}", state.path.display()))?;
+    *state.stored.lock().unwrap() = settings;
+    *state.plaintext_tokens.lock().unwrap() = plaintext;
+
+    let effective = state.effective();
+    let client = client_for(app, &effective);
+    let view = state.view(client.base_url().to_string());
+    let backend_changed =
+        previous_profile != effective.active().name || previous_url != client.base_url();
+    crate::set_client(app, client);
+
+    if let Err(err) = tray::show_profiles(app, &effective) {
+        eprintln!("Failed to update tray profiles: {err}");
+    }
+    if let Err(err) = quick_search::apply_shortcut(app, &effective) {
+        eprintln!("{err}");
+    }
+    if let Err(err) = clippings::apply_shortcut(app, &effective) {
+        eprintln!("{err}");
+    }
+    watcher::apply_settings(app);
+    app.emit("settings-changed", &view)
+        .map_err(|e| e.to_string())?;
+    if backend_changed {
+        let profile = ProfileChanged {
+            name: effective.active().name.clone(),
+            base_url: view.backend_url.clone(),
+        };
+        app.emit("profile-changed", &profile)
+            .map_err(|e| e.to_string())?;
+        let handle = app.clone();
+        thread::spawn(move || sources::sources_changed(&handle));
+    }
+    Ok(view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use context_cache_desktop_lib::settings::BackendProfile;
+
+    fn with_tokens(tokens: &[(&str, Option<&str>)]) -> Settings {
+        Settings {
+            profiles: tokens
+                .iter()
+                .map(|(name, token)| BackendProfile {
+                    name: name.to_string(),
+                    auth_token: token.map(str::to_string),
+                    ..BackendProfile::default()
+                })
+                .collect(),
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn restores_tokens_the_webview_never_saw() {
+        let previous = with_tokens(&[
+            ("local", Some("l")),
+            ("work", Some("w")),
+            ("lab", Some("x")),
+        ]);
+        // "work" was renamed to "office", "lab" gets a new token and "home" is new.
+        let mut settings = with_tokens(&[
+            ("local", None),
+            ("office", None),
+            ("lab", None),
+            ("home", None),
+        ]);
+        let changes = TokenChanges::from([
+            ("lab".to_string(), Some("y".to_string())),
+            ("home".to_string(), Some(String::new())),
+        ]);
+        restore_tokens(&previous, &mut settings, &changes);
+        let tokens: Vec<_> = settings
+            .profiles
+            .iter()
+            .map(|profile| profile.auth_token.as_deref())
+            .collect();
+        assert_eq!(tokens, [Some("l"), Some("w"), Some("y"), None]);
+
+        let mut settings = with_tokens(&[("local", Some("leaked")), ("work", None)]);
+        let changes = TokenChanges::from([("work".to_string(), None)]);
+        restore_tokens(&previous, &mut settings, &changes);
+        assert_eq!(settings.profiles[0].auth_token.as_deref(), Some("l"));
+        assert_eq!(settings.profiles[1].auth_token, None);
+    }
+
+    #[test]
+    fn keeps_tokens_in_the_file_without_a_keyring() {
+        let previous = with_tokens(&[("local", None), ("work", None)]);
+        let settings = with_tokens(&[("local", None), ("work", Some("secret"))]);
+        let mut calls = Vec::new();
+        let plaintext = store_tokens(&previous, &settings, &BTreeSet::new(), |name, _| {
+            calls.push(name.to_string());
+            Err("no keyring".into())
+        });
+        assert_eq!(calls, ["work"]);
+        assert_eq!(plaintext, BTreeSet::from(["work".to_string()]));
+
+        // A later save with the token unchanged retries the keyring and keeps the token on failure.
+        let plaintext = store_tokens(&settings, &settings, &plaintext, |_, _| {
+            Err("no keyring".into())
+        });
+        assert_eq!(plaintext, BTreeSet::from(["work".to_string()]));
+    }
+
+    #[test]
+    fn moves_tokens_out_of_the_file_once_the_keyring_takes_them() {
+        let settings = with_tokens(&[("local", None), ("work", Some("secret"))]);
+        let plaintext = BTreeSet::from(["work".to_string()]);
+        let mut stored = Vec::new();
+        let plaintext = store_tokens(&settings, &settings, &plaintext, |name, token| {
+            stored.push((name.to_string(), token.map(str::to_string)));
+            Ok(())
+        });
+        assert!(plaintext.is_empty());
+        assert_eq!(stored, [("work".to_string(), Some("secret".to_string()))]);
+    }
+
+    #[test]
+    fn writes_only_changed_tokens_and_deletes_removed_profiles() {
+        let previous = with_tokens(&[("local", None), ("old", Some("a")), ("work", Some("b"))]);
+        let settings = with_tokens(&[("local", None), ("work", Some("c"))]);
+        let mut calls = Vec::new();
+        let plaintext = store_tokens(&previous, &settings, &BTreeSet::new(), |name, token| {
+            calls.push((name.to_string(), token.map(str::to_string)));
+            Ok(())
+        });
+        assert!(plaintext.is_empty());
+        assert_eq!(
+            calls,
+            [
+                ("work".to_string(), Some("c".to_string())),
+                ("old".to_string(), None)
+            ]
+        );
+    }
+}
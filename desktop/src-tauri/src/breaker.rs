@@ -0,0 +1,176 @@
+//! Circuit breaker that makes backend calls fail fast while the backend is down.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Consecutive failed calls that open the circuit.
+const FAILURE_THRESHOLD: u32 = 3;
+/// How long an open circuit rejects calls before letting a trial call through.
+const COOL_DOWN: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through.
+    Closed,
+    /// Calls fail immediately until the cool-down ends.
+    Open,
+    /// The cool-down ended; one trial call decides whether the circuit closes again.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    failures: u32,
+    opened_at: Option<Instant>,
+    /// A half-open circuit let a trial call through and is waiting for its outcome.
+    trial_in_flight: bool,
+}
+
+/// Shared by every client of one backend, so a failure seen by one command spares the others.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    inner: Mutex<Inner>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                failures: 0,
+                opened_at: None,
+                trial_in_flight: false,
+            }),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    pub fn state(&self) -> CircuitState {
+        match self.inner.lock().unwrap().opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < COOL_DOWN => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// `Err` with the remaining cool-down while the circuit is open.
+    ///
+    /// Once half-open, only the first caller gets through; the rest wait for its outcome,
+    /// which must be reported with `record_success`, `record_failure` or `release`.
+    pub fn check(&self) -> Result<(), Duration> {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(opened_at) = inner.opened_at else {
+            return Ok(());
+        };
+        let elapsed = opened_at.elapsed();
+        if elapsed < COOL_DOWN {
+            return Err(COOL_DOWN.saturating_sub(elapsed));
+        }
+        if inner.trial_in_flight {
+            return Err(Duration::ZERO);
+        }
+        inner.trial_in_flight = true;
+        Ok(())
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.failures = 0;
+        inner.opened_at = None;
+        inner.trial_in_flight = false;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.failures = inner.failures.saturating_add(1);
+        inner.trial_in_flight = false;
+        // A failed trial call re-opens the circuit for another full cool-down.
+        if inner.failures >= FAILURE_THRESHOLD {
+            inner.opened_at = Some(Instant::now());
+        }
+    }
+
+    /// Ends a call that said nothing about the backend's health, letting another trial through.
+    pub fn release(&self) {
+        self.inner.lock().unwrap().trial_in_flight = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(breaker: &CircuitBreaker) {
+        for _ in 0..FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+    }
+
+    fn end_cool_down(breaker: &CircuitBreaker) {
+        let mut inner = breaker.inner.lock().unwrap();
+        inner.opened_at = Some(Instant::now() - COOL_DOWN);
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::default();
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure();
+            assert_eq!(breaker.state(), CircuitState::Closed);
+            assert!(breaker.check().is_ok());
+        }
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        let retry_in = breaker.check().unwrap_err();
+        assert!(retry_in > Duration::ZERO && retry_in <= COOL_DOWN);
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let breaker = CircuitBreaker::default();
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        breaker.record_success();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn half_open_lets_one_trial_through() {
+        let breaker = CircuitBreaker::default();
+        open(&breaker);
+        end_cool_down(&breaker);
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.check(), Err(Duration::ZERO));
+        breaker.release();
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn successful_trial_closes_the_circuit() {
+        let breaker = CircuitBreaker::default();
+        open(&breaker);
+        end_cool_down(&breaker);
+        breaker.check().unwrap();
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn failed_trial_reopens_the_circuit() {
+        let breaker = CircuitBreaker::default();
+        open(&breaker);
+        end_cool_down(&breaker);
+        breaker.check().unwrap();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.check().unwrap_err() > Duration::ZERO);
+    }
+}
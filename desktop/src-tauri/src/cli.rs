@@ -0,0 +1,434 @@
+//! Headless subcommands: talk to the backend with the GUI's settings, without a window or tray.
+
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use context_cache_desktop_lib::api_token;
+use context_cache_desktop_lib::client::BackendClient;
+use context_cache_desktop_lib::dto::{ChunkResult, IngestRequest, QueryFilters};
+use context_cache_desktop_lib::settings::{self, Settings};
+use serde::Serialize;
+
+use crate::{ingest, instance};
+
+/// First arguments that select the CLI instead of the GUI.
+const COMMANDS: &[&str] = &[
+    "query",
+    "ingest",
+    "sources",
+    "why",
+    "health",
+    "token",
+    "help",
+    "--help",
+    "-h",
+    "--version",
+    "-V",
+];
+
+const SNIPPET_LENGTH: usize = 80;
+
+#[derive(Parser)]
+#[command(
+    name = "context-cache-desktop",
+    version,
+    about = "Context Cache desktop shell"
+)]
+#[command(after_help = "Run without a subcommand to start the desktop app.")]
+struct Cli {
+    /// Print raw JSON responses instead of tables.
+    #[arg(long, global = true)]
+    json: bool,
+    /// Backend profile to use instead of the active one.
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Search the index.
+    Query(QueryArgs),
+    /// Ingest files, folders or registered sources (all sources by default).
+    Ingest {
+        /// Ingest only these source ids.
+        #[arg(long = "source", value_name = "ID", conflicts_with = "paths")]
+        sources: Vec<String>,
+        /// Files or folders to ingest.
+        paths: Vec<PathBuf>,
+    },
+    /// Manage registered sources.
+    Sources {
+        #[command(subcommand)]
+        command: SourcesCommand,
+    },
+    /// Show the stored provenance of a past query.
+    Why { query_id: String },
+    /// Check that the backend is reachable.
+    Health,
+    /// Print the local backend's API token from the OS keyring.
+    Token,
+}
+
+#[derive(Args)]
+struct QueryArgs {
+    query: String,
+    /// Number of results; defaults to the setting.
+    #[arg(short, long)]
+    k: Option<u32>,
+    #[arg(long, overrides_with = "no_rerank")]
+    rerank: bool,
+    #[arg(long)]
+    no_rerank: bool,
+    #[arg(long, overrides_with = "no_hybrid")]
+    hybrid: bool,
+    #[arg(long)]
+    no_hybrid: bool,
+    /// Restrict to documents with this tag; repeatable.
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+    /// Restrict to this source id; repeatable.
+    #[arg(long = "source", value_name = "ID")]
+    sources: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum SourcesCommand {
+    /// List registered sources.
+    List,
+}
+
+/// True when `args` (without the program name) ask for a subcommand rather than the GUI.
+///
+/// The global `--json` and `--profile <NAME>` may come first; flags alone also mean the CLI,
+/// so clap can tell that a subcommand is missing.
+pub fn is_command(args: &[String]) -> bool {
+    let mut args = args.iter();
+    let mut global = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => global = true,
+            "--profile" => {
+                args.next();
+                global = true;
+            }
+            flag if flag.starts_with("--profile=") => global = true,
+            first => return COMMANDS.contains(&first),
+        }
+    }
+    global
+}
+
+/// Runs a subcommand and returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    attach_console();
+    let cli = match Cli::try_parse_from(
+        std::iter::once("context-cache-desktop".to_string()).chain(args.iter().cloned()),
+    ) {
+        Ok(cli) => cli,
+        Err(err) => {
+            let _ = err.print();
+            return err.exit_code();
+        }
+    };
+    match execute(cli) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            1
+        }
+    }
+}
+
+fn execute(cli: Cli) -> Result<i32, String> {
+    if let Command::Token = cli.command {
+        println!("{}", api_token::local_token()?);
+        return Ok(0);
+    }
+    let path = settings::default_path();
+    let mut settings = Settings::load(&path).unwrap_or_else(|err| {
+        eprintln!("Failed to read {}: {err}; using defaults", path.display());
+        Settings::default()
+    });
+    settings.apply_env_overrides();
+    if let Some(name) = cli.profile {
+        if settings.profile(&name).is_none() {
+            return Err(format!("Unknown backend profile \"{name}\""));
+        }
+        settings.active_profile = name;
+    }
+    // Only the profile this command talks to needs a token, so the keyring is left alone
+    // when one is configured or the environment provides it.
+    if settings.active().auth_token.is_none() {
+        settings.active_mut().auth_token = keyring_token(&settings);
+    }
+    let client = BackendClient::from_settings(&settings);
+    match cli.command {
+        Command::Query(args) => query(&client, &settings, args, cli.json),
+        Command::Ingest { sources, paths } => ingest(&client, sources, paths, cli.json),
+        Command::Sources {
+            command: SourcesCommand::List,
+        } => {
+            let sources = client.list_sources()?;
+            if cli.json {
+                return print_json(&sources);
+            }
+            let rows = sources
+                .into_iter()
+                .map(|source| {
+                    vec![
+                        source.id,
+                        serde_json::to_value(source.kind)
+                            .ok()
+                            .and_then(|kind| kind.as_str().map(str::to_string))
+                            .unwrap_or_default(),
+                        source.label.unwrap_or_default(),
+                        source.uri,
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "KIND", "LABEL", "URI"], rows);
+            Ok(0)
+        }
+        Command::Why { query_id } => {
+            let response = client.why(&query_id)?;
+            if cli.json {
+                return print_json(&response);
+            }
+            println!("query_id: {}", response.query_id);
+            print_results(&response.results);
+            Ok(0)
+        }
+        Command::Health => {
+            let health = client.health()?;
+            if cli.json {
+                print_json(&health)?;
+            } else {
+                let state = if health.ok { "ok" } else { "unhealthy" };
+                println!("{state} {}", client.base_url());
+            }
+            Ok(if health.ok { 0 } else { 1 })
+        }
+        Command::Token => unreachable!("handled before loading settings"),
+    }
+}
+
+/// The active profile's token from the keyring; the local profile falls back to the shared token.
+fn keyring_token(settings: &Settings) -> Option<String> {
+    let name = &settings.active().name;
+    match api_token::profile_token(name) {
+        Ok(Some(token)) => return Some(token),
+        Ok(None) => {}
+        Err(err) => eprintln!("warning: {err}"),
+    }
+    if !settings.active_is_local() {
+        return None;
+    }
+    api_token::local_token()
+        .map_err(|err| eprintln!("warning: {err}"))
+        .ok()
+}
+
+fn query(
+    client: &BackendClient,
+    settings: &Settings,
+    args: QueryArgs,
+    json: bool,
+) -> Result<i32, String> {
+    if args.query.trim().is_empty() {
+        return Err("Query must not be empty".into());
+    }
+    let flag = |on: bool, off: bool| match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    let filters = (!args.tags.is_empty() || !args.sources.is_empty()).then(|| QueryFilters {
+        source_ids: Some(args.sources).filter(|ids| !ids.is_empty()),
+        document_ids: None,
+        tags: Some(args.tags).filter(|tags| !tags.is_empty()),
+    });
+    let request = settings.query_request(
+        args.query,
+        args.k,
+        flag(args.rerank, args.no_rerank),
+        flag(args.hybrid, args.no_hybrid),
+        filters,
+    );
+    let response = client.query(&request)?;
+    if json {
+        return print_json(&response);
+    }
+    println!("query_id: {}", response.query_id);
+    print_results(&response.results);
+    Ok(0)
+}
+
+fn ingest(
+    client: &BackendClient,
+    sources: Vec<String>,
+    paths: Vec<PathBuf>,
+    json: bool,
+) -> Result<i32, String> {
+    let request = if !paths.is_empty() {
+        IngestRequest {
+            // The backend resolves relative paths against its own working directory.
+            paths: Some(
+                paths
+                    .iter()
+                    .map(|path| instance::absolute(path).to_string_lossy().into_owned())
+                    .collect(),
+            ),
+            ..IngestRequest::default()
+        }
+    } else if !sources.is_empty() {
+        IngestRequest {
+            sources: Some(sources),
+            ..IngestRequest::default()
+        }
+    } else {
+        IngestRequest::all()
+    };
+    let response = client.ingest(&request)?;
+    let code = if response.stats.failed > 0 { 1 } else { 0 };
+    if json {
+        print_json(&response)?;
+        return Ok(code);
+    }
+    let rows = response
+        .results
+        .iter()
+        .map(|result| {
+            vec![
+                result.status.clone(),
+                result.path.clone(),
+                result.detail.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    print_table(&["STATUS", "PATH", "DETAIL"], rows);
+    println!("{}", ingest::summarize(&response.stats));
+    Ok(code)
+}
+
+fn print_results(results: &[ChunkResult]) {
+    let rows = results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| {
+            vec![
+                (index + 1).to_string(),
+                format!("{:.3}", result.score),
+                result_title(result),
+                snippet(&result.text),
+            ]
+        })
+        .collect();
+    print_table(&["#", "SCORE", "DOCUMENT", "TEXT"], rows);
+}
+
+fn result_title(result: &ChunkResult) -> String {
+    let provenance = &result.provenance;
+    if let Some(title) = provenance
+        .get("meta")
+        .and_then(|meta| meta.get("title"))
+        .and_then(|title| title.as_str())
+        .filter(|title| !title.trim().is_empty())
+    {
+        return title.to_string();
+    }
+    let location = ["uri", "external_id"]
+        .iter()
+        .find_map(|key| provenance.get(*key).and_then(|value| value.as_str()))
+        .unwrap_or(&result.document_id);
+    location
+        .rsplit(['/', '\\'])
+        .find(|part| !part.is_empty())
+        .unwrap_or(location)
+        .to_string()
+}
+
+fn snippet(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > SNIPPET_LENGTH {
+        format!("{}…", flat.chars().take(SNIPPET_LENGTH).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<i32, String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{text}");
+    Ok(0)
+}
+
+/// Prints left-aligned columns; the last column is never padded.
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let header_row = headers.iter().map(|header| header.to_string()).collect();
+    for row in std::iter::once(header_row).chain(rows) {
+        let last = row.len().saturating_sub(1);
+        let line = row
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                if index == last {
+                    cell.clone()
+                } else {
+                    format!("{cell:<width$}", width = widths[index])
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{line}");
+    }
+}
+
+/// Release builds use the Windows GUI subsystem; reuse the launching terminal for output.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // SAFETY: `AttachConsole` has no preconditions; failure just leaves output unattached.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(args: &[&str]) -> bool {
+        is_command(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn recognises_subcommands_after_global_flags() {
+        assert!(command(&["health"]));
+        assert!(command(&["--json", "query", "foo"]));
+        assert!(command(&["--profile", "work", "health"]));
+        assert!(command(&["--profile=work", "--json", "sources", "list"]));
+        assert!(command(&["--profile", "work"]));
+        assert!(command(&["--version"]));
+    }
+
+    #[test]
+    fn leaves_launch_arguments_to_the_gui() {
+        assert!(!command(&[]));
+        assert!(!command(&["notes.md"]));
+        assert!(!command(&["./query"]));
+        assert!(!command(&["--query", "health"]));
+        assert!(!command(&["ctxc://search?q=x"]));
+        assert!(!command(&["--profile", "work", "notes.md"]));
+    }
+}
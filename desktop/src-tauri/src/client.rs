@@ -0,0 +1,413 @@
+//! Typed HTTP client for the Context Cache backend API.
+
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::breaker::{CircuitBreaker, CircuitState};
+use crate::dto::{
+    DeleteRequest, DeleteResponse, HealthResponse, IngestRequest, IngestResponse, QueryRequest,
+    QueryResponse, SourceCreateRequest, SourceResponse, SourceUpdateRequest, UpsertTagsRequest,
+    UpsertTagsResponse, WhyResponse,
+};
+use crate::settings::{NetworkSettings, Settings, TlsSettings};
+use crate::tls;
+
+pub const DEFAULT_HOST: &str = "http://127.0.0.1:5173";
+
+/// First retry delay; each further retry doubles it. The actual delay is jittered down to half.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+const MAX_BACKOFF_DOUBLINGS: u32 = 5;
+
+/// Characters escaped in an id used as a path segment, so `/`, `?` or `#` cannot change the route.
+const PATH_SEGMENT: &AsciiSet = NON_ALPHANUMERIC;
+
+/// `/sources/{id}` with the id percent-encoded.
+pub fn source_path(source_id: &str) -> String {
+    format!("/sources/{}", utf8_percent_encode(source_id, PATH_SEGMENT))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Method {
+    Get,
+    Post,
+    Patch,
+    Delete,
+    Options,
+}
+
+impl Method {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+            Method::Options => "OPTIONS",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The backend could not be reached at all (refused, DNS, reset, ...).
+    ///
+    /// `connected` is set when the connection was made, so the request may have arrived.
+    Connection {
+        url: String,
+        message: String,
+        connected: bool,
+    },
+    /// The backend answered with a non-2xx status.
+    Status {
+        url: String,
+        status: u16,
+        body: String,
+    },
+    /// The response body did not match the expected shape.
+    Decode { url: String, message: String },
+    /// The TLS setup or handshake failed, typically over an untrusted certificate.
+    Tls { url: String, message: String },
+    /// The request would have sent the token over plain HTTP to another machine.
+    Insecure { url: String },
+    /// Recent requests failed, so this one was not attempted.
+    CircuitOpen { url: String, retry_in: Duration },
+}
+
+impl ClientError {
+    /// True when the backend looks down rather than rejecting this particular request.
+    pub fn is_outage(&self) -> bool {
+        match self {
+            ClientError::Connection { .. } | ClientError::CircuitOpen { .. } => true,
+            ClientError::Status { status, .. } => matches!(status, 502..=504),
+            _ => false,
+        }
+    }
+
+    /// True when the request never left this machine, so sending it again cannot apply it twice.
+    pub fn is_unsent(&self) -> bool {
+        matches!(
+            self,
+            ClientError::Connection {
+                connected: false,
+                ..
+            } | ClientError::CircuitOpen { .. }
+        )
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Connection { url, message, .. } => {
+                write!(f, "Could not connect to {url}: {message}")
+            }
+            ClientError::Status { url, status, body } if body.is_empty() => {
+                write!(f, "Request to {url} failed with HTTP {status}")
+            }
+            ClientError::Status { url, status, body } => {
+                write!(f, "Request to {url} failed with HTTP {status}: {body}")
+            }
+            ClientError::Decode { url, message } => {
+                write!(f, "Unexpected response from {url}: {message}")
+            }
+            ClientError::Tls { url, message } => {
+                write!(f, "Secure connection to {url} failed: {message}")
+            }
+            ClientError::Insecure { url } => write!(
+                f,
+                "Refusing to send the API token to {url} over plain HTTP; use https://"
+            ),
+            ClientError::CircuitOpen { url, retry_in } => write!(
+                f,
+                "Backend at {url} is not responding; retrying in {}s",
+                retry_in.as_secs().max(1)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<ClientError> for String {
+    fn from(err: ClientError) -> Self {
+        err.to_string()
+    }
+}
+
+#[derive(Clone)]
+pub struct BackendClient {
+    base_url: String,
+    auth_token: Option<String>,
+    tls: TlsSettings,
+    network: NetworkSettings,
+    agent: ureq::Agent,
+    /// Why the profile's TLS settings could not be applied; every request reports it.
+    tls_error: Option<String>,
+    /// Shared by clones, so the health monitor sees what commands run into.
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl BackendClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        let mut client = Self {
+            base_url,
+            auth_token: None,
+            tls: TlsSettings::default(),
+            network: NetworkSettings::default(),
+            agent: ureq::AgentBuilder::new().build(),
+            tls_error: None,
+            breaker: Arc::default(),
+        };
+        client.build_agent();
+        client
+    }
+
+    pub fn from_settings(settings: &Settings) -> Self {
+        let profile = settings.active();
+        Self::new(settings.backend_url())
+            .with_auth_token(profile.auth_token.clone())
+            .with_tls(&profile.tls)
+            .with_network(&settings.network)
+    }
+
+    /// Trusts the profile's CA bundle and pinned certificates.
+    pub fn with_tls(mut self, tls: &TlsSettings) -> Self {
+        self.tls = tls.clone();
+        self.build_agent();
+        self
+    }
+
+    /// Applies timeouts and the GET retry budget.
+    pub fn with_network(mut self, network: &NetworkSettings) -> Self {
+        self.network = network.clone();
+        self.build_agent();
+        self
+    }
+
+    fn build_agent(&mut self) {
+        let builder = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(self.network.connect_timeout_secs));
+        match tls::agent(builder, &self.tls) {
+            Ok(agent) => {
+                self.agent = agent;
+                self.tls_error = None;
+            }
+            Err(err) => self.tls_error = Some(err),
+        }
+    }
+
+    /// Sends `Authorization: Bearer <token>` with every request when set.
+    pub fn with_auth_token(mut self, token: Option<String>) -> Self {
+        self.auth_token = token.filter(|token| !token.is_empty());
+        self
+    }
+
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        self.breaker.state()
+    }
+
+    pub fn health(&self) -> Result<HealthResponse, ClientError> {
+        self.send(Method::Get, "/health", None::<&()>)
+    }
+
+    pub fn ingest(&self, request: &IngestRequest) -> Result<IngestResponse, ClientError> {
+        self.send(Method::Post, "/ingest", Some(request))
+    }
+
+    pub fn query(&self, request: &QueryRequest) -> Result<QueryResponse, ClientError> {
+        self.send(Method::Post, "/query", Some(request))
+    }
+
+    pub fn why(&self, query_id: &str) -> Result<WhyResponse, ClientError> {
+        self.send(
+            Method::Get,
+            &format!("/why/{}", utf8_percent_encode(query_id, PATH_SEGMENT)),
+            None::<&()>,
+        )
+    }
+
+    pub fn list_sources(&self) -> Result<Vec<SourceResponse>, ClientError> {
+        self.send(Method::Get, "/sources", None::<&()>)
+    }
+
+    pub fn create_source(
+        &self,
+        request: &SourceCreateRequest,
+    ) -> Result<SourceResponse, ClientError> {
+        self.send(Method::Post, "/sources", Some(request))
+    }
+
+    pub fn update_source(
+        &self,
+        source_id: &str,
+        request: &SourceUpdateRequest,
+    ) -> Result<SourceResponse, ClientError> {
+        self.send(Method::Patch, &source_path(source_id), Some(request))
+    }
+
+    pub fn delete_source(&self, source_id: &str) -> Result<DeleteResponse, ClientError> {
+        self.send(Method::Delete, &source_path(source_id), None::<&()>)
+    }
+
+    pub fn delete(&self, request: &DeleteRequest) -> Result<DeleteResponse, ClientError> {
+        self.send(Method::Post, "/delete", Some(request))
+    }
+
+    pub fn upsert_tags(
+        &self,
+        request: &UpsertTagsRequest,
+    ) -> Result<UpsertTagsResponse, ClientError> {
+        self.send(Method::Post, "/tags/upsert", Some(request))
+    }
+
+    /// Issues an `OPTIONS` request and returns the advertised `Allow` header, if any.
+    pub fn options(&self, path: &str) -> Result<Option<String>, ClientError> {
+        let response = self.call(Method::Options, path, None::<&()>)?;
+        Ok(response.header("allow").map(str::to_string))
+    }
+
+    pub fn send<B, T>(&self, method: Method, path: &str, body: Option<&B>) -> Result<T, ClientError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let response = self.call(method, path, body)?;
+        response.into_json().map_err(|err| ClientError::Decode {
+            url: self.url(path),
+            message: err.to_string(),
+        })
+    }
+
+    /// Sends one request, retrying idempotent ones while the backend looks down.
+    ///
+    /// `/health` skips the circuit breaker's check so the health monitor can close it again.
+    fn call<B>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<ureq::Response, ClientError>
+    where
+        B: Serialize + ?Sized,
+    {
+        let url = self.url(path);
+        let is_health = path == "/health";
+        let retries = match method {
+            Method::Get | Method::Options if !is_health => self.network.get_retries,
+            _ => 0,
+        };
+        let mut attempt = 0;
+        loop {
+            if !is_health {
+                self.breaker
+                    .check()
+                    .map_err(|retry_in| ClientError::CircuitOpen {
+                        url: url.clone(),
+                        retry_in,
+                    })?;
+            }
+            let result = self.attempt(method, &url, self.timeout(path), body);
+            match &result {
+                Ok(_) => self.breaker.record_success(),
+                Err(err) if err.is_outage() => self.breaker.record_failure(),
+                Err(ClientError::Status { .. }) => self.breaker.record_success(),
+                Err(_) => self.breaker.release(),
+            }
+            match result {
+                Err(err) if err.is_outage() && attempt < retries => {
+                    let delay = RETRY_BASE_DELAY * 2u32.pow(attempt.min(MAX_BACKOFF_DOUBLINGS));
+                    thread::sleep(rand::thread_rng().gen_range(delay / 2..=delay));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn timeout(&self, path: &str) -> Duration {
+        let network = &self.network;
+        let secs = if path == "/health" {
+            network.health_timeout_secs
+        } else if path == "/ingest" || path.starts_with("/ingest/") {
+            network.ingest_timeout_secs
+        } else {
+            network.request_timeout_secs
+        };
+        Duration::from_secs(secs)
+    }
+
+    fn attempt<B>(
+        &self,
+        method: Method,
+        url: &str,
+        timeout: Duration,
+        body: Option<&B>,
+    ) -> Result<ureq::Response, ClientError>
+    where
+        B: Serialize + ?Sized,
+    {
+        if let Some(message) = &self.tls_error {
+            return Err(ClientError::Tls {
+                url: url.to_string(),
+                message: message.clone(),
+            });
+        }
+        if self.auth_token.is_some() && !tls::may_send_token(url) {
+            return Err(ClientError::Insecure {
+                url: url.to_string(),
+            });
+        }
+        let mut request = self.agent.request(method.as_str(), url).timeout(timeout);
+        if let Some(token) = &self.auth_token {
+            request = request.set("Authorization", &format!("Bearer {token}"));
+        }
+        let result = match body {
+            Some(payload) => request.send_json(payload),
+            None => request.call(),
+        };
+        result.map_err(|err| match err {
+            ureq::Error::Status(status, response) => ClientError::Status {
+                url: url.to_string(),
+                status,
+                body: response.into_string().unwrap_or_default(),
+            },
+            ureq::Error::Transport(transport) => match tls::describe(&transport) {
+                Some(message) => ClientError::Tls {
+                    url: url.to_string(),
+                    message,
+                },
+                None => ClientError::Connection {
+                    url: url.to_string(),
+                    message: transport.to_string(),
+                    connected: !matches!(
+                        transport.kind(),
+                        ureq::ErrorKind::Dns
+                            | ureq::ErrorKind::ConnectionFailed
+                            | ureq::ErrorKind::ProxyConnect
+                    ),
+                },
+            },
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
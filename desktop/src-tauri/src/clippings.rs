@@ -0,0 +1,143 @@
+//! "Save Clipboard to Context Cache": clipboard text becomes a Markdown note in a managed source.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use context_cache_desktop_lib::dto::{IngestRequest, SourceCreateRequest, SourceKind};
+use context_cache_desktop_lib::settings::Settings;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use url::Url;
+
+use crate::shortcuts::BoundShortcut;
+use crate::{app_settings, ingest, sources};
+
+const SOURCE_LABEL: &str = "Clippings";
+const SOURCE_INCLUDE_GLOB: &str = "**/*.md";
+
+/// The global shortcut currently bound to clipboard capture.
+#[derive(Default)]
+pub struct ClippingShortcut(BoundShortcut);
+
+pub fn init(app: &AppHandle) {
+    app.manage(ClippingShortcut::default());
+    if let Err(err) = apply_shortcut(app, &app_settings::current(app)) {
+        eprintln!("{err}");
+    }
+}
+
+/// Rebinds the global shortcut when the configured one changed.
+pub fn apply_shortcut(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    app.state::<ClippingShortcut>().0.rebind(
+        app,
+        &settings.clippings.shortcut,
+        "clipboard capture",
+        capture,
+    )
+}
+
+/// Saves the clipboard text as a note and ingests it, reporting failures as a notification.
+pub fn capture(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = save_clipboard(&app).await {
+            eprintln!("Failed to save clipboard: {err}");
+            if app_settings::current(&app).notifications.ingest_failed {
+                ingest::notify(&app, "Could not save clipboard", &err);
+            }
+        }
+    });
+}
+
+async fn save_clipboard(app: &AppHandle) -> Result<(), String> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|err| format!("Could not read the clipboard: {err}"))?;
+    if text.trim().is_empty() {
+        return Err("The clipboard has no text to save".into());
+    }
+    let folder = app_settings::current(app).clippings.folder();
+    let handle = app.clone();
+    let note = crate::blocking(move || {
+        // Write first so the clipping survives even when the backend is unreachable.
+        let note = write_note(&folder, &text)?;
+        ensure_source(&handle, &folder).map_err(|err| {
+            format!(
+                "Saved {} but could not register the {SOURCE_LABEL} source: {err}",
+                note.display()
+            )
+        })?;
+        Ok(note)
+    })
+    .await?;
+    let request = IngestRequest {
+        paths: Some(vec![note.to_string_lossy().into_owned()]),
+        ..IngestRequest::default()
+    };
+    ingest::start_ingest(app.clone(), request).await?;
+    Ok(())
+}
+
+/// Writes `text` to a new timestamped note in `folder`.
+fn write_note(folder: &Path, text: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(folder)
+        .map_err(|err| format!("Cannot create {}: {err}", folder.display()))?;
+    let now = Local::now();
+    // No `:` in file names, which Windows rejects.
+    let stem = now.format("%Y-%m-%d %H%M%S").to_string();
+    let body = format!(
+        "# Clipping {}\n\n{}\n",
+        now.format("%Y-%m-%d %H:%M"),
+        text.trim_end()
+    );
+    let mut attempt = 1;
+    loop {
+        let name = match attempt {
+            1 => format!("{stem}.md"),
+            n => format!("{stem} ({n}).md"),
+        };
+        let path = folder.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(body.as_bytes())
+                    .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+                return Ok(path);
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(err) => return Err(format!("Failed to create {}: {err}", path.display())),
+        }
+    }
+}
+
+/// Registers `folder` as the Clippings source on the active backend unless it already is.
+///
+/// Performs blocking I/O; call it from a worker thread.
+fn ensure_source(app: &AppHandle, folder: &Path) -> Result<(), String> {
+    let client = crate::client(app);
+    let folder = folder
+        .canonicalize()
+        .map_err(|err| format!("Cannot resolve {}: {err}", folder.display()))?;
+    let registered = client.list_sources()?.iter().any(|source| {
+        Url::parse(&source.uri)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .and_then(|path| path.canonicalize().ok())
+            .is_some_and(|path| path == folder)
+    });
+    if registered {
+        return Ok(());
+    }
+    let source = SourceCreateRequest {
+        label: Some(SOURCE_LABEL.to_string()),
+        kind: SourceKind::Folder,
+        uri: folder.to_string_lossy().into_owned(),
+        include_glob: Some(SOURCE_INCLUDE_GLOB.to_string()),
+        exclude_glob: None,
+    };
+    sources::register_source(app, &client, source)?;
+    sources::sources_changed(app);
+    Ok(())
+}
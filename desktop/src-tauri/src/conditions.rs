@@ -0,0 +1,148 @@
+//! Machine state the scheduler waits for. `None` means the platform cannot tell.
+
+use std::time::Duration;
+
+/// Whether the machine runs on mains power.
+pub fn on_ac_power() -> Option<bool> {
+    platform::on_ac_power()
+}
+
+/// Time since the last keyboard or mouse input.
+pub fn idle_time() -> Option<Duration> {
+    platform::idle_time()
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use std::time::Duration;
+
+    const POWER_SUPPLIES: &str = "/sys/class/power_supply";
+
+    pub fn on_ac_power() -> Option<bool> {
+        let mut mains = None;
+        let mut discharging = false;
+        for entry in fs::read_dir(POWER_SUPPLIES).ok()?.flatten() {
+            let path = entry.path();
+            match read(&path, "type").as_deref() {
+                Some("Mains") => {
+                    let online = read(&path, "online").as_deref() == Some("1");
+                    mains = Some(mains.unwrap_or(false) || online);
+                }
+                Some("Battery") => {
+                    discharging |= read(&path, "status").as_deref() == Some("Discharging");
+                }
+                _ => {}
+            }
+        }
+        // Some laptops expose only the battery; a desktop exposes nothing at all.
+        mains.or(discharging.then_some(false))
+    }
+
+    fn read(dir: &Path, name: &str) -> Option<String> {
+        fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|text| text.trim().to_string())
+    }
+
+    /// X11 only, and only with `xprintidle` installed; Wayland has no portable equivalent.
+    pub fn idle_time() -> Option<Duration> {
+        let output = Command::new("xprintidle").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let millis = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .ok()?;
+        Some(Duration::from_millis(millis))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+    use std::time::Duration;
+
+    pub fn on_ac_power() -> Option<bool> {
+        let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        if text.contains("'AC Power'") {
+            Some(true)
+        } else if text.contains("'Battery Power'") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub fn idle_time() -> Option<Duration> {
+        let output = Command::new("ioreg")
+            .args(["-c", "IOHIDSystem", "-d", "4"])
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let nanos = text
+            .lines()
+            .find_map(|line| line.split_once("\"HIDIdleTime\" = "))?
+            .1
+            .trim()
+            .parse()
+            .ok()?;
+        Some(Duration::from_nanos(nanos))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::time::Duration;
+
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    pub fn on_ac_power() -> Option<bool> {
+        // SAFETY: the struct is plain integers, so all-zero is valid, and it outlives the call.
+        let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return None;
+        }
+        match status.ACLineStatus {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn idle_time() -> Option<Duration> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        // SAFETY: `info` is initialised with its size as the API requires.
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
+            return None;
+        }
+        // Both counters wrap after ~49 days; the wrapping difference stays correct.
+        // SAFETY: `GetTickCount` has no preconditions.
+        let now = unsafe { GetTickCount() };
+        Some(Duration::from_millis(u64::from(
+            now.wrapping_sub(info.dwTime),
+        )))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use std::time::Duration;
+
+    pub fn on_ac_power() -> Option<bool> {
+        None
+    }
+
+    pub fn idle_time() -> Option<Duration> {
+        None
+    }
+}
@@ -0,0 +1,112 @@
+//! `ctxc://` permalinks to documents, chunks and past queries.
+
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use url::Url;
+
+pub const SCHEME: &str = "ctxc";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeepLink {
+    /// `ctxc://doc/<document_id>[?chunk=<chunk_id>]`
+    Document {
+        document_id: String,
+        chunk_id: Option<String>,
+    },
+    /// `ctxc://chunk/<chunk_id>`
+    Chunk { chunk_id: String },
+    /// `ctxc://query/<query_id>`
+    Query { query_id: String },
+}
+
+impl DeepLink {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let url = Url::parse(text.trim()).map_err(|err| format!("Invalid link {text}: {err}"))?;
+        if url.scheme() != SCHEME {
+            return Err(format!("Not a {SCHEME}:// link: {text}"));
+        }
+        let id = url
+            .path_segments()
+            .and_then(|mut segments| segments.next())
+            .filter(|id| !id.is_empty())
+            .map(|id| percent_decode_str(id).decode_utf8_lossy().into_owned())
+            .ok_or_else(|| format!("Link {text} is missing an identifier"))?;
+        match url.host_str() {
+            Some("doc") => Ok(DeepLink::Document {
+                document_id: id,
+                chunk_id: url
+                    .query_pairs()
+                    .find(|(key, _)| key == "chunk")
+                    .map(|(_, value)| value.into_owned())
+                    .filter(|value| !value.is_empty()),
+            }),
+            Some("chunk") => Ok(DeepLink::Chunk { chunk_id: id }),
+            Some("query") => Ok(DeepLink::Query { query_id: id }),
+            _ => Err(format!(
+                "Unsupported link {text}; expected {SCHEME}://doc/, chunk/ or query/"
+            )),
+        }
+    }
+
+    /// True when `text` looks like one of our links rather than a path or query argument.
+    pub fn is_link(text: &str) -> bool {
+        text.trim_start()
+            .to_ascii_lowercase()
+            .starts_with(&format!("{SCHEME}://"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_document_links_with_and_without_chunk() {
+        assert_eq!(
+            DeepLink::parse("ctxc://doc/abc123?chunk=c9").unwrap(),
+            DeepLink::Document {
+                document_id: "abc123".into(),
+                chunk_id: Some("c9".into()),
+            }
+        );
+        assert_eq!(
+            DeepLink::parse("  ctxc://doc/abc123?chunk=  ").unwrap(),
+            DeepLink::Document {
+                document_id: "abc123".into(),
+                chunk_id: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_chunk_and_query_links() {
+        assert_eq!(
+            DeepLink::parse("ctxc://chunk/c1").unwrap(),
+            DeepLink::Chunk {
+                chunk_id: "c1".into()
+            }
+        );
+        assert_eq!(
+            DeepLink::parse("ctxc://query/q%2F1").unwrap(),
+            DeepLink::Query {
+                query_id: "q/1".into()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_other_links() {
+        assert!(DeepLink::parse("https://doc/abc").is_err());
+        assert!(DeepLink::parse("ctxc://doc/").is_err());
+        assert!(DeepLink::parse("ctxc://folder/abc").is_err());
+        assert!(DeepLink::parse("not a link").is_err());
+    }
+
+    #[test]
+    fn recognises_links_case_insensitively() {
+        assert!(DeepLink::is_link("  CTXC://doc/abc"));
+        assert!(!DeepLink::is_link("/home/me/ctxc://notes.md"));
+        assert!(!DeepLink::is_link("ctxc:doc/abc"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Ingests files and folders dropped onto the main window.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use context_cache_desktop_lib::dto::IngestRequest;
+use context_cache_desktop_lib::globs::SourceFilter;
+use serde::Serialize;
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, WindowEvent};
+
+use crate::ingest;
+
+const WINDOW_LABEL: &str = "main";
+
+#[derive(Serialize, Clone)]
+struct DropHover {
+    active: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct DroppedFile {
+    path: String,
+    /// `processed`, `skipped` and `error` come from the backend; `unsupported` never left the shell.
+    status: String,
+    detail: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct DropResults {
+    files: Vec<DroppedFile>,
+    error: Option<String>,
+}
+
+/// Listens for drag-and-drop on the main window.
+pub fn init(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(WINDOW_LABEL) else {
+        return;
+    };
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        let WindowEvent::DragDrop(event) = event else {
+            return;
+        };
+        match event {
+            DragDropEvent::Enter { .. } => emit_hover(&handle, true),
+            DragDropEvent::Leave => emit_hover(&handle, false),
+            DragDropEvent::Drop { paths, .. } => {
+                emit_hover(&handle, false);
+                spawn_drop(&handle, paths.clone());
+            }
+            _ => {}
+        }
+    });
+}
+
+fn emit_hover(app: &AppHandle, active: bool) {
+    if let Err(err) = app.emit_to(WINDOW_LABEL, "file-drop-hover", DropHover { active }) {
+        eprintln!("Failed to emit drop hover: {err}");
+    }
+}
+
+fn spawn_drop(app: &AppHandle, paths: Vec<PathBuf>) {
+    if let Err(err) = app.emit_to(WINDOW_LABEL, "file-drop-started", ()) {
+        eprintln!("Failed to emit drop start: {err}");
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let results = ingest_dropped(&app, paths).await;
+        if let Err(err) = app.emit_to(WINDOW_LABEL, "file-drop-results", results) {
+            eprintln!("Failed to emit drop results: {err}");
+        }
+    });
+}
+
+async fn ingest_dropped(app: &AppHandle, paths: Vec<PathBuf>) -> DropResults {
+    let collected = crate::blocking(move || Ok(collect(&paths))).await;
+    let (supported, mut files) = match collected {
+        Ok(collected) => collected,
+        Err(error) => {
+            return DropResults {
+                files: Vec::new(),
+                error: Some(error),
+            }
+        }
+    };
+    if supported.is_empty() {
+        return DropResults {
+            files,
+            error: Some("None of the dropped files are a supported type".into()),
+        };
+    }
+    let request = IngestRequest {
+        paths: Some(supported.clone()),
+        ..IngestRequest::default()
+    };
+    match ingest::start_ingest(app.clone(), request).await {
+        Ok(response) => {
+            let mut reported: Vec<DroppedFile> = response
+                .results
+                .into_iter()
+                .map(|result| DroppedFile {
+                    path: result.path,
+                    status: result.status,
+                    detail: result.detail,
+                })
+                .collect();
+            // Anything the backend did not mention still deserves a row.
+            for path in supported {
+                if !reported.iter().any(|file| file.path == path) {
+                    reported.push(DroppedFile {
+                        path,
+                        status: "skipped".into(),
+                        detail: Some("Not reported by the backend".into()),
+                    });
+                }
+            }
+            reported.append(&mut files);
+            DropResults {
+                files: reported,
+                error: None,
+            }
+        }
+        Err(error) => {
+            files.splice(
+                0..0,
+                supported.into_iter().map(|path| DroppedFile {
+                    path,
+                    status: "error".into(),
+                    detail: Some(error.clone()),
+                }),
+            );
+            DropResults {
+                files,
+                error: Some(error),
+            }
+        }
+    }
+}
+
+/// Splits dropped paths into supported files to ingest and rows for everything filtered out.
+fn collect(paths: &[PathBuf]) -> (Vec<String>, Vec<DroppedFile>) {
+    let filter = SourceFilter::new(None, None).expect("default globs are valid");
+    let mut supported = Vec::new();
+    let mut unsupported = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            supported.extend(filter.walk(path));
+        } else if filter.accepts(path) {
+            supported.push(path.clone());
+        } else {
+            unsupported.push(DroppedFile {
+                path: path.to_string_lossy().into_owned(),
+                status: "unsupported".into(),
+                detail: Some("File type is not indexed".into()),
+            });
+        }
+    }
+    // The backend reports resolved paths, so send those and match its results against them.
+    let supported = supported
+        .into_iter()
+        .map(|path| resolve(&path).to_string_lossy().into_owned())
+        .collect();
+    (supported, unsupported)
+}
+
+/// Like Python's `Path.resolve()`: absolute, with symlinks followed.
+fn resolve(path: &Path) -> PathBuf {
+    let Ok(resolved) = fs::canonicalize(path) else {
+        return path.to_path_buf();
+    };
+    // Windows canonical paths carry a `\\?\` prefix that `resolve()` does not produce.
+    #[cfg(windows)]
+    if let Some(plain) = resolved
+        .to_str()
+        .and_then(|text| text.strip_prefix(r"\\?\"))
+    {
+        if !plain.starts_with("UNC\\") {
+            return PathBuf::from(plain);
+        }
+    }
+    resolved
+}
@@ -0,0 +1,183 @@
+//! Request and response types mirroring `context_cache.models.dto`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const DEFAULT_K: u32 = 8;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    #[default]
+    Folder,
+    File,
+    Mbox,
+    Markdown,
+    NotionExport,
+    Other,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceCreateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub kind: SourceKind,
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_glob: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_glob: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceUpdateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_glob: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_glob: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceResponse {
+    pub id: String,
+    pub label: Option<String>,
+    pub kind: SourceKind,
+    pub uri: String,
+    pub include_glob: Option<String>,
+    pub exclude_glob: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sources: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paths: Option<Vec<String>>,
+    #[serde(default)]
+    pub all: bool,
+}
+
+impl IngestRequest {
+    pub fn all() -> Self {
+        Self {
+            all: true,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestStats {
+    #[serde(default)]
+    pub processed: u64,
+    #[serde(default)]
+    pub skipped: u64,
+    #[serde(default)]
+    pub failed: u64,
+    #[serde(default)]
+    pub chunks: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestFileResult {
+    pub document_id: Option<String>,
+    pub path: String,
+    pub status: String,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestResponse {
+    pub job_id: String,
+    pub stats: IngestStats,
+    #[serde(default)]
+    pub results: Vec<IngestFileResult>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryFilters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_ids: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_ids: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRequest {
+    pub query: String,
+    #[serde(default = "default_k")]
+    pub k: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hybrid: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<QueryFilters>,
+}
+
+fn default_k() -> u32 {
+    DEFAULT_K
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkResult {
+    pub chunk_id: String,
+    pub document_id: String,
+    pub score: f64,
+    pub text: String,
+    pub start_char: i64,
+    pub end_char: i64,
+    #[serde(default)]
+    pub provenance: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResponse {
+    pub query_id: String,
+    pub results: Vec<ChunkResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhyResponse {
+    pub query_id: String,
+    pub results: Vec<ChunkResult>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeleteRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_ids: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub hard: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteResponse {
+    pub status: String,
+    pub deleted: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertTagsRequest {
+    pub document_ids: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertTagsResponse {
+    pub updated: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthResponse {
+    pub ok: bool,
+}
@@ -0,0 +1,194 @@
+//! Source include/exclude globs, matched the way the backend pipeline matches them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+/// Backend defaults for `watch.include_glob` / `watch.exclude_glob`.
+pub const DEFAULT_INCLUDE_GLOB: &str = "**/*.{md,txt,pdf,docx,eml,mbox}";
+pub const DEFAULT_EXCLUDE_GLOB: &str = "**/{.git,.obsidian,node_modules}/**";
+
+/// `fnmatch` semantics: `*` crosses `/`, and case only matters where the filesystem's does.
+const OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: !cfg!(windows),
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// A glob with `{a,b}` alternatives expanded into separate patterns.
+#[derive(Debug, Clone)]
+pub struct GlobSet {
+    patterns: Vec<Pattern>,
+}
+
+impl GlobSet {
+    pub fn new(glob: &str) -> Result<Self, String> {
+        let patterns = expand_braces(glob)
+            .iter()
+            .map(|pattern| {
+                Pattern::new(pattern).map_err(|err| format!("Invalid glob \"{glob}\": {err}"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Matches a full path, using `/` separators on every platform like `Path.as_posix()`.
+    pub fn matches(&self, path: &Path) -> bool {
+        let text = path.to_string_lossy().replace('\\', "/");
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches_with(&text, OPTIONS))
+    }
+
+    /// True when every file inside `dir` would match, so the walk can skip it.
+    pub fn covers_dir(&self, dir: &Path) -> bool {
+        self.matches(&dir.join("x"))
+    }
+}
+
+/// Include/exclude pair for one source.
+#[derive(Debug, Clone)]
+pub struct SourceFilter {
+    include: GlobSet,
+    exclude: GlobSet,
+}
+
+impl SourceFilter {
+    pub fn new(include: Option<&str>, exclude: Option<&str>) -> Result<Self, String> {
+        Ok(Self {
+            include: GlobSet::new(include.unwrap_or(DEFAULT_INCLUDE_GLOB))?,
+            exclude: GlobSet::new(exclude.unwrap_or(DEFAULT_EXCLUDE_GLOB))?,
+        })
+    }
+
+    pub fn accepts(&self, path: &Path) -> bool {
+        self.include.matches(path) && !self.exclude.matches(path)
+    }
+
+    pub fn skips_dir(&self, dir: &Path) -> bool {
+        self.exclude.covers_dir(dir)
+    }
+
+    /// Files under `root` this filter accepts; symlinked directories are not followed.
+    pub fn walk(&self, root: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut pending = vec![root.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                match entry.file_type() {
+                    Ok(kind) if kind.is_dir() && !self.skips_dir(&path) => pending.push(path),
+                    Ok(kind) if !kind.is_dir() && path.is_file() && self.accepts(&path) => {
+                        files.push(path)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+}
+
+/// Expands `{a,b}` alternatives (nested groups included) like the backend's `_expand_patterns`.
+pub fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_string()];
+    };
+    let mut depth = 0;
+    let mut close = None;
+    for (index, ch) in pattern[open..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(open + index);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let Some(close) = close else {
+        return vec![pattern.to_string()];
+    };
+    let (prefix, body, suffix) = (
+        &pattern[..open],
+        &pattern[open + 1..close],
+        &pattern[close + 1..],
+    );
+    let mut alternatives = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (index, ch) in body.char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(&body[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(&body[start..]);
+    alternatives
+        .into_iter()
+        .flat_map(|alternative| expand_braces(&format!("{prefix}{alternative}{suffix}")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(glob: &str, path: &str) -> bool {
+        GlobSet::new(glob).unwrap().matches(Path::new(path))
+    }
+
+    #[test]
+    fn star_crosses_separators_like_fnmatch() {
+        assert!(matches("*.md", "/home/me/notes/todo.md"));
+        assert!(matches("**/*.md", "/home/me/notes/todo.md"));
+        assert!(matches("/home/me/*", "/home/me/notes/deep/file.txt"));
+        assert!(!matches("**/*.md", "/home/me/notes/todo.txt"));
+    }
+
+    #[test]
+    fn dotfiles_are_not_special() {
+        assert!(matches("**/*.md", "/home/me/.notes/.draft.md"));
+        assert!(matches(DEFAULT_EXCLUDE_GLOB, "/repo/.git/config"));
+        assert!(matches(
+            DEFAULT_EXCLUDE_GLOB,
+            "/repo/a/node_modules/x/readme.md"
+        ));
+        assert!(!matches(DEFAULT_EXCLUDE_GLOB, "/repo/.github/readme.md"));
+    }
+
+    #[test]
+    fn case_follows_the_platform() {
+        assert_eq!(matches("**/*.md", "/home/me/README.MD"), cfg!(windows));
+    }
+
+    #[test]
+    fn expands_nested_braces() {
+        assert_eq!(expand_braces("a{b,c{d,e}}f"), ["abf", "acdf", "acef"]);
+        assert_eq!(expand_braces("no-braces"), ["no-braces"]);
+        assert_eq!(expand_braces("open{only"), ["open{only"]);
+    }
+
+    #[test]
+    fn default_filter_accepts_documents_outside_excluded_dirs() {
+        let filter = SourceFilter::new(None, None).unwrap();
+        assert!(filter.accepts(Path::new("/vault/daily/2024-01-01.md")));
+        assert!(filter.accepts(Path::new("/mail/archive.mbox")));
+        assert!(!filter.accepts(Path::new("/vault/.obsidian/workspace.md")));
+        assert!(!filter.accepts(Path::new("/vault/image.png")));
+        assert!(filter.skips_dir(Path::new("/vault/.git")));
+        assert!(!filter.skips_dir(Path::new("/vault/daily")));
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use context_cache_desktop_lib::breaker::CircuitState;
+use context_cache_desktop_lib::client::ClientError;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::sidecar::Sidecar;
+use crate::{outbox, sources, tray};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Re-read `/sources` for the tray every this many polls while connected.
+const SOURCE_REFRESH_POLLS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendState {
+    Starting,
+    Connected,
+    Degraded,
+    Unreachable,
+}
+
+impl BackendState {
+    pub fn label(self) -> &'static str {
+        match self {
+            BackendState::Starting => "Starting backend…",
+            BackendState::Connected => "Connected",
+            BackendState::Degraded => "Backend degraded",
+            BackendState::Unreachable => "Backend unreachable",
+        }
+    }
+
+    /// Colour of the tray badge; `None` shows the plain icon.
+    pub fn badge_color(self) -> Option<[u8; 3]> {
+        match self {
+            BackendState::Starting => Some([250, 204, 21]),
+            BackendState::Connected => None,
+            BackendState::Degraded => Some([249, 115, 22]),
+            BackendState::Unreachable => Some([220, 38, 38]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub state: BackendState,
+    pub detail: Option<String>,
+    /// Whether commands are currently failing fast instead of reaching the backend.
+    pub circuit: CircuitState,
+}
+
+pub struct HealthMonitor {
+    status: Mutex<BackendStatus>,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self {
+            status: Mutex::new(BackendStatus {
+                state: BackendState::Starting,
+                detail: None,
+                circuit: CircuitState::Closed,
+            }),
+        }
+    }
+}
+
+impl HealthMonitor {
+    pub fn current(&self) -> BackendStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+#[tauri::command]
+pub fn backend_status(monitor: State<'_, HealthMonitor>) -> BackendStatus {
+    monitor.current()
+}
+
+pub fn start(app: &AppHandle) {
+    app.manage(HealthMonitor::default());
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut first = true;
+        let mut polls_since_refresh = 0;
+        loop {
+            let status = probe(&app);
+            let monitor = app.state::<HealthMonitor>();
+            let changed = {
+                let mut current = monitor.status.lock().unwrap();
+                let changed =
+                    first || current.state != status.state || current.circuit != status.circuit;
+                *current = status.clone();
+                changed
+            };
+            if changed {
+                if let Err(err) = tray::apply_backend_state(&app, status.state) {
+                    eprintln!("Failed to update tray: {err}");
+                }
+                if let Err(err) = app.emit("backend-status", &status) {
+                    eprintln!("Failed to emit backend-status: {err}");
+                }
+            }
+            if status.state == BackendState::Connected {
+                outbox::spawn_replay(&app);
+                if changed || polls_since_refresh >= SOURCE_REFRESH_POLLS {
+                    polls_since_refresh = 0;
+                    sources::sources_changed(&app);
+                }
+                polls_since_refresh += 1;
+            }
+            first = false;
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+fn probe(app: &AppHandle) -> BackendStatus {
+    let client = crate::client(app);
+    let sidecar_starting = app
+        .try_state::<Sidecar>()
+        .is_some_and(|sidecar| sidecar.is_starting());
+    let (state, detail) = match client.health() {
+        Ok(health) if health.ok => (BackendState::Connected, None),
+        Ok(_) => (
+            BackendState::Degraded,
+            Some("Backend reported it is not healthy".to_string()),
+        ),
+        Err(err @ ClientError::Connection { .. }) if sidecar_starting => {
+            (BackendState::Starting, Some(err.to_string()))
+        }
+        Err(
+            err @ (ClientError::Connection { .. }
+            | ClientError::Tls { .. }
+            | ClientError::Insecure { .. }
+            | ClientError::CircuitOpen { .. }),
+        ) => (BackendState::Unreachable, Some(err.to_string())),
+        Err(err) => (BackendState::Degraded, Some(err.to_string())),
+    };
+    // Read after the probe, which closes the circuit when the backend is back.
+    BackendStatus {
+        state,
+        detail,
+        circuit: client.circuit_state(),
+    }
+}
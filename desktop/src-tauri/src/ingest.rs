@@ -0,0 +1,331 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use context_cache_desktop_lib::client::BackendClient;
+use context_cache_desktop_lib::dto::{
+    IngestFileResult, IngestRequest, IngestResponse, IngestStats,
+};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::outbox::{self, Sent};
+use crate::{app_settings, jobs};
+
+#[derive(Serialize, Clone)]
+struct IngestFinished<'a> {
+    /// The backend's `ingest_jobs` id; the last one for a job split into several requests.
+    job_id: &'a str,
+    /// The desktop job history entry, when the shell tracked this ingest.
+    shell_job_id: Option<&'a str>,
+    stats: &'a IngestStats,
+    results: &'a [IngestFileResult],
+    message: String,
+}
+
+#[derive(Serialize, Clone)]
+struct IngestFailed<'a> {
+    error: &'a str,
+    message: String,
+}
+
+/// Key used for `{"all": true}` ingests, which overlap with every source.
+const ALL_SOURCES: &str = "*";
+
+/// Tracks which sources currently have an ingest in flight.
+#[derive(Default)]
+pub struct IngestTracker {
+    in_flight: Mutex<HashSet<String>>,
+}
+
+impl IngestTracker {
+    fn begin(&self, app_handle: &AppHandle, keys: Vec<String>) -> Result<InFlight, String> {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if overlaps(&in_flight, &keys) {
+            return Err("An ingest for this source is already running".into());
+        }
+        in_flight.extend(keys.iter().cloned());
+        Ok(InFlight {
+            app_handle: app_handle.clone(),
+            keys,
+        })
+    }
+}
+
+/// Sources claimed for a running ingest; dropping it releases them.
+pub struct InFlight {
+    app_handle: AppHandle,
+    keys: Vec<String>,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        let tracker = self.app_handle.state::<IngestTracker>();
+        let mut in_flight = tracker.in_flight.lock().unwrap();
+        for key in &self.keys {
+            in_flight.remove(key);
+        }
+    }
+}
+
+/// Whether an ingest claiming `keys` would overlap one already in flight.
+fn overlaps(in_flight: &HashSet<String>, keys: &[String]) -> bool {
+    if keys.iter().any(|key| key == ALL_SOURCES) {
+        in_flight.iter().any(|key| !key.starts_with("path:"))
+    } else {
+        in_flight.contains(ALL_SOURCES) || keys.iter().any(|key| in_flight.contains(key))
+    }
+}
+
+fn in_flight_keys(request: &IngestRequest) -> Vec<String> {
+    if let Some(paths) = request.paths.as_ref().filter(|paths| !paths.is_empty()) {
+        return paths.iter().map(|path| format!("path:{path}")).collect();
+    }
+    match request
+        .sources
+        .as_ref()
+        .filter(|sources| !sources.is_empty())
+    {
+        Some(sources) if !request.all => sources.clone(),
+        _ => vec![ALL_SOURCES.to_string()],
+    }
+}
+
+/// Claims the request's sources, or fails when an overlapping ingest is already running.
+pub fn claim(app_handle: &AppHandle, request: &IngestRequest) -> Result<InFlight, String> {
+    claim_within(app_handle, request, &[])
+}
+
+/// [`claim`] for a path ingest that also claims the sources the paths belong to.
+pub fn claim_within(
+    app_handle: &AppHandle,
+    request: &IngestRequest,
+    sources: &[String],
+) -> Result<InFlight, String> {
+    let mut keys = in_flight_keys(request);
+    keys.extend(sources.iter().cloned());
+    app_handle.state::<IngestTracker>().begin(app_handle, keys)
+}
+
+#[tauri::command]
+pub async fn trigger_ingest(app_handle: AppHandle) -> Result<IngestResponse, String> {
+    start_ingest(app_handle, IngestRequest::all()).await
+}
+
+/// Runs an ingest on the blocking pool so neither the tray nor the webview stall.
+pub async fn start_ingest(
+    app_handle: AppHandle,
+    request: IngestRequest,
+) -> Result<IngestResponse, String> {
+    crate::blocking(move || {
+        let in_flight = match claim(&app_handle, &request) {
+            Ok(in_flight) => in_flight,
+            Err(err) => {
+                if app_settings::current(&app_handle)
+                    .notifications
+                    .ingest_failed
+                {
+                    notify(&app_handle, "Ingest already running", &err);
+                }
+                return Err(err);
+            }
+        };
+        let client = crate::client(&app_handle);
+        run_ingest(&app_handle, &client, &request, in_flight)
+    })
+    .await
+}
+
+/// Fire-and-forget variant of [`start_ingest`] for tray and dialog callbacks.
+pub fn spawn_ingest(app_handle: &AppHandle, request: IngestRequest) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = start_ingest(app_handle, request).await {
+            eprintln!("Failed to ingest: {err}");
+        }
+    });
+}
+
+/// [`spawn_ingest`] for a request whose sources the caller already claimed.
+pub fn spawn_claimed(app_handle: &AppHandle, request: IngestRequest, in_flight: InFlight) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = crate::blocking(move || {
+            let client = crate::client(&app_handle);
+            run_ingest(&app_handle, &client, &request, in_flight)
+        })
+        .await;
+        if let Err(err) = result {
+            eprintln!("Failed to ingest: {err}");
+        }
+    });
+}
+
+/// Runs an ingest and reports the outcome to the webview and as a desktop notification.
+///
+/// `_in_flight` keeps the request's sources claimed until the ingest is over.
+fn run_ingest(
+    app_handle: &AppHandle,
+    client: &BackendClient,
+    request: &IngestRequest,
+    _in_flight: InFlight,
+) -> Result<IngestResponse, String> {
+    let prefs = app_settings::current(app_handle).notifications;
+    match jobs::run(app_handle, client, request) {
+        Ok((job_id, Sent::Done(response))) => {
+            report_finished(app_handle, Some(&job_id), &response)?;
+            Ok(response)
+        }
+        Ok((_, Sent::Queued(_))) => {
+            if prefs.ingest_failed {
+                notify(app_handle, "Ingest queued", outbox::QUEUED_MESSAGE);
+            }
+            Err(outbox::QUEUED_MESSAGE.into())
+        }
+        Err(error) => {
+            report_failed(app_handle, &error)?;
+            Err(error)
+        }
+    }
+}
+
+pub fn report_finished(
+    app_handle: &AppHandle,
+    shell_job_id: Option<&str>,
+    response: &IngestResponse,
+) -> Result<(), String> {
+    let message = summarize(&response.stats);
+    app_handle
+        .emit(
+            "ingest-finished",
+            IngestFinished {
+                job_id: &response.job_id,
+                shell_job_id,
+                stats: &response.stats,
+                results: &response.results,
+                message: message.clone(),
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    if app_settings::current(app_handle)
+        .notifications
+        .ingest_finished
+    {
+        notify(app_handle, "Ingest finished", &message);
+    }
+    Ok(())
+}
+
+pub fn report_failed(app_handle: &AppHandle, error: &str) -> Result<(), String> {
+    app_handle
+        .emit(
+            "ingest-failed",
+            IngestFailed {
+                error,
+                message: "Ingest failed".into(),
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    if app_settings::current(app_handle)
+        .notifications
+        .ingest_failed
+    {
+        notify(app_handle, "Ingest failed", error);
+    }
+    Ok(())
+}
+
+pub fn summarize(stats: &IngestStats) -> String {
+    format!(
+        "Processed {} file{} ({} skipped, {} failed), {} chunk{} indexed",
+        stats.processed,
+        if stats.processed == 1 { "" } else { "s" },
+        stats.skipped,
+        stats.failed,
+        stats.chunks,
+        if stats.chunks == 1 { "" } else { "s" },
+    )
+}
+
+pub fn notify(app_handle: &AppHandle, title: &str, body: &str) {
+    if let Err(err) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        eprintln!("Failed to show notification: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(sources: &[&str], paths: &[&str], all: bool) -> IngestRequest {
+        let list = |items: &[&str]| {
+            (!items.is_empty()).then(|| items.iter().map(|item| item.to_string()).collect())
+        };
+        IngestRequest {
+            sources: list(sources),
+            paths: list(paths),
+            all,
+        }
+    }
+
+    fn running(requests: &[IngestRequest]) -> HashSet<String> {
+        requests.iter().flat_map(in_flight_keys).collect()
+    }
+
+    #[test]
+    fn keys_requests_by_what_they_ingest() {
+        assert_eq!(
+            in_flight_keys(&request(&[], &["/a.md"], false)),
+            ["path:/a.md"]
+        );
+        assert_eq!(
+            in_flight_keys(&request(&["s1", "s2"], &[], false)),
+            ["s1", "s2"]
+        );
+        assert_eq!(in_flight_keys(&request(&["s1"], &[], true)), [ALL_SOURCES]);
+        assert_eq!(in_flight_keys(&request(&[], &[], false)), [ALL_SOURCES]);
+    }
+
+    #[test]
+    fn refuses_only_overlapping_ingests() {
+        let source = running(&[request(&["s1"], &[], false)]);
+        assert!(overlaps(
+            &source,
+            &in_flight_keys(&request(&["s1"], &[], false))
+        ));
+        assert!(overlaps(&source, &in_flight_keys(&request(&[], &[], true))));
+        assert!(!overlaps(
+            &source,
+            &in_flight_keys(&request(&["s2"], &[], false))
+        ));
+        // A watcher batch claims its paths together with the sources holding them.
+        assert!(overlaps(&source, &["path:/s1/a.md".into(), "s1".into()]));
+
+        let all = running(&[request(&[], &[], true)]);
+        assert!(overlaps(
+            &all,
+            &in_flight_keys(&request(&["s2"], &[], false))
+        ));
+        assert!(overlaps(
+            &all,
+            &in_flight_keys(&request(&[], &["/a.md"], false))
+        ));
+
+        let paths = running(&[request(&[], &["/a.md"], false)]);
+        assert!(overlaps(
+            &paths,
+            &in_flight_keys(&request(&[], &["/a.md"], false))
+        ));
+        assert!(!overlaps(
+            &paths,
+            &in_flight_keys(&request(&[], &["/b.md"], false))
+        ));
+        assert!(!overlaps(&paths, &in_flight_keys(&request(&[], &[], true))));
+    }
+}
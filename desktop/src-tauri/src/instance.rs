@@ -0,0 +1,393 @@
+//! Keeps the shell single-instance and hands later launches' arguments to the running one.
+//!
+//! Both hold an exclusive lock file. Unix listens on a per-user domain socket; elsewhere a
+//! loopback TCP port is advertised in a file together with a nonce both ends check.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use context_cache_desktop_lib::deep_link::DeepLink;
+use context_cache_desktop_lib::dto::IngestRequest;
+use context_cache_desktop_lib::settings::APP_IDENTIFIER;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use tauri::AppHandle;
+
+use crate::{ingest, navigation, open_ui};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const CONNECT_ATTEMPTS: u32 = 10;
+const CONNECT_RETRY: Duration = Duration::from_millis(200);
+
+pub enum Instance {
+    /// This process owns the app; keep the value alive and pass it to [`listen`].
+    Primary(imp::Primary),
+    /// Another process already runs the app.
+    Secondary,
+}
+
+/// What a launch asked for: `ctxc://` links, `--query <text>` and files or folders to ingest.
+#[derive(Debug, Default)]
+pub struct LaunchArgs {
+    pub links: Vec<String>,
+    pub query: Option<String>,
+    pub paths: Vec<PathBuf>,
+}
+
+impl LaunchArgs {
+    pub fn parse(args: &[String]) -> Self {
+        let mut launch = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if DeepLink::is_link(arg) {
+                launch.links.push(arg.clone());
+            } else if arg == "--query" || arg == "-q" {
+                launch.query = args.next().cloned();
+            } else if let Some(query) = arg.strip_prefix("--query=") {
+                launch.query = Some(query.to_string());
+            } else if arg.starts_with('-') {
+                // Platform launchers add their own flags (e.g. macOS `-psn_…`).
+                continue;
+            } else {
+                launch.paths.push(absolute(Path::new(arg)));
+            }
+        }
+        launch
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty() && self.query.is_none() && self.paths.is_empty()
+    }
+}
+
+/// Claims the app for this process, or reports that another instance has it.
+pub fn acquire() -> Instance {
+    match imp::acquire() {
+        Ok(Some(primary)) => Instance::Primary(primary),
+        Ok(None) => Instance::Secondary,
+        Err(err) => {
+            // Without the socket we cannot coordinate; running alone beats not running.
+            eprintln!("Failed to set up single-instance socket: {err}");
+            Instance::Primary(imp::Primary::detached())
+        }
+    }
+}
+
+/// Sends `args` to the running instance; returns false when it could not be reached.
+pub fn forward(args: &[String]) -> bool {
+    let mut attempts = 0;
+    let mut stream = loop {
+        match imp::connect() {
+            Ok(stream) => break stream,
+            Err(_) if attempts + 1 < CONNECT_ATTEMPTS => {
+                // The primary may hold the lock but not be listening yet.
+                attempts += 1;
+                thread::sleep(CONNECT_RETRY);
+            }
+            Err(err) => {
+                eprintln!("Could not reach the running instance: {err}");
+                return false;
+            }
+        }
+    };
+    let _ = stream.set_write_timeout(Some(CONNECT_TIMEOUT));
+    let Ok(mut line) = serde_json::to_string(args) else {
+        return false;
+    };
+    line.push('\n');
+    match stream.write_all(line.as_bytes()) {
+        Ok(()) => true,
+        Err(err) => {
+            eprintln!("Failed to forward arguments to the running instance: {err}");
+            false
+        }
+    }
+}
+
+/// Accepts arguments forwarded by later launches and acts on them.
+pub fn listen(app: &AppHandle, primary: imp::Primary) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let Some(listener) = primary.listener() else {
+            return;
+        };
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    eprintln!("Instance socket error: {err}");
+                    continue;
+                }
+            };
+            let mut reader = BufReader::new(stream);
+            if let Err(err) = primary.admit(&mut reader) {
+                eprintln!("Rejected a connection on the instance socket: {err}");
+                continue;
+            }
+            let mut line = String::new();
+            if let Err(err) = reader.read_line(&mut line) {
+                eprintln!("Failed to read forwarded arguments: {err}");
+                continue;
+            }
+            match serde_json::from_str::<Vec<String>>(&line) {
+                Ok(args) => {
+                    let launch = LaunchArgs::parse(&args);
+                    if launch.is_empty() {
+                        // A plain second launch just brings the existing window forward.
+                        if let Err(err) = open_ui(app.clone()) {
+                            eprintln!("Failed to open UI: {err}");
+                        }
+                    }
+                    handle(&app, launch);
+                }
+                Err(err) => eprintln!("Ignoring malformed forwarded arguments: {err}"),
+            }
+        }
+    });
+}
+
+/// Acts on a launch's arguments, whether they came from this process or a forwarded one.
+pub fn handle(app: &AppHandle, launch: LaunchArgs) {
+    for link in &launch.links {
+        navigation::handle_link(app, link);
+    }
+    if let Some(query) = launch.query.filter(|query| !query.trim().is_empty()) {
+        let path = format!(
+            "/search?q={}",
+            utf8_percent_encode(&query, NON_ALPHANUMERIC)
+        );
+        navigation::navigate(app, path, None);
+    }
+    if !launch.paths.is_empty() {
+        let request = IngestRequest {
+            paths: Some(
+                launch
+                    .paths
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect(),
+            ),
+            ..IngestRequest::default()
+        };
+        ingest::spawn_ingest(app, request);
+    }
+}
+
+/// Resolves relative paths against this launch's working directory before they cross processes.
+pub fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+fn runtime_dir() -> PathBuf {
+    dirs::runtime_dir().unwrap_or_else(std::env::temp_dir)
+}
+
+#[cfg(unix)]
+pub mod imp {
+    use std::fs::{self, File, OpenOptions};
+    use std::io::{self, BufReader};
+    use std::os::fd::AsRawFd;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::PathBuf;
+
+    use super::{runtime_dir, APP_IDENTIFIER};
+
+    pub struct Primary {
+        listener: Option<UnixListener>,
+        /// Held for the life of the process; the kernel drops the lock when it exits.
+        _lock: Option<File>,
+    }
+
+    impl Primary {
+        pub fn detached() -> Self {
+            Self {
+                listener: None,
+                _lock: None,
+            }
+        }
+
+        pub fn listener(&self) -> Option<&UnixListener> {
+            self.listener.as_ref()
+        }
+
+        /// Only this user can reach the socket, so every connection is another launch.
+        pub fn admit(&self, _reader: &mut BufReader<UnixStream>) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn path(extension: &str) -> PathBuf {
+        // SAFETY: `getuid` has no preconditions and cannot fail.
+        let uid = unsafe { libc::getuid() };
+        runtime_dir().join(format!("{APP_IDENTIFIER}-{uid}.{extension}"))
+    }
+
+    pub fn acquire() -> io::Result<Option<Primary>> {
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path("lock"))?;
+        // SAFETY: the descriptor is valid for as long as `lock` is alive.
+        if unsafe { libc::flock(lock.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            let err = io::Error::last_os_error();
+            return match err.raw_os_error() {
+                Some(libc::EWOULDBLOCK) => Ok(None),
+                _ => Err(err),
+            };
+        }
+        let socket = path("sock");
+        // Whoever held the lock before us is gone, so any socket file is stale.
+        let _ = fs::remove_file(&socket);
+        Ok(Some(Primary {
+            listener: Some(UnixListener::bind(socket)?),
+            _lock: Some(lock),
+        }))
+    }
+
+    pub fn connect() -> io::Result<UnixStream> {
+        UnixStream::connect(path("sock"))
+    }
+}
+
+#[cfg(not(unix))]
+pub mod imp {
+    use std::fs::{self, File, OpenOptions, TryLockError};
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+    use std::path::PathBuf;
+
+    use rand::RngCore;
+
+    use super::{runtime_dir, APP_IDENTIFIER, CONNECT_TIMEOUT};
+
+    pub struct Primary {
+        listener: Option<TcpListener>,
+        /// Advertised next to the port; later launches expect it as a greeting and send it back,
+        /// so neither side talks to some other program on that port.
+        nonce: String,
+        /// Held for the life of the process; the system drops the lock when it exits.
+        _lock: Option<File>,
+    }
+
+    impl Primary {
+        pub fn detached() -> Self {
+            Self {
+                listener: None,
+                nonce: String::new(),
+                _lock: None,
+            }
+        }
+
+        pub fn listener(&self) -> Option<&TcpListener> {
+            self.listener.as_ref()
+        }
+
+        /// Greets a connection with the nonce and checks that it answers with the same one.
+        pub fn admit(&self, reader: &mut BufReader<TcpStream>) -> io::Result<()> {
+            let stream = reader.get_mut();
+            stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+            writeln!(stream, "{}", self.nonce)?;
+            let mut answer = String::new();
+            reader.read_line(&mut answer)?;
+            if answer.trim_end() != self.nonce {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "the peer did not send the instance nonce",
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    fn path(extension: &str) -> PathBuf {
+        runtime_dir().join(format!("{APP_IDENTIFIER}.{extension}"))
+    }
+
+    pub fn acquire() -> io::Result<Option<Primary>> {
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path("lock"))?;
+        // A `LockFileEx` lock on Windows, released when the process exits.
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(err)) => return Err(err),
+        }
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let nonce: String = nonce.iter().map(|byte| format!("{byte:02x}")).collect();
+        let port = listener.local_addr()?.port();
+        fs::write(path("port"), format!("{port} {nonce}"))?;
+        Ok(Some(Primary {
+            listener: Some(listener),
+            nonce,
+            _lock: Some(lock),
+        }))
+    }
+
+    /// Connects to the primary and checks its greeting, so a stale port file never leads the
+    /// arguments to another program.
+    pub fn connect() -> io::Result<TcpStream> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed instance port file");
+        let advertised = fs::read_to_string(path("port"))?;
+        let (port, nonce) = advertised.trim().split_once(' ').ok_or_else(invalid)?;
+        let port: u16 = port.parse().map_err(|_| invalid())?;
+        let mut stream = TcpStream::connect_timeout(
+            &SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+            CONNECT_TIMEOUT,
+        )?;
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        let mut greeting = String::new();
+        BufReader::new(&stream).read_line(&mut greeting)?;
+        if greeting.trim_end() != nonce {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                "the advertised port belongs to another program",
+            ));
+        }
+        writeln!(stream, "{nonce}")?;
+        Ok(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_links_queries_and_paths() {
+        let launch = LaunchArgs::parse(&args(&[
+            "ctxc://doc/abc",
+            "-psn_0_12345",
+            "--query",
+            "rust traits",
+            "/tmp/notes.md",
+        ]));
+        assert_eq!(launch.links, ["ctxc://doc/abc"]);
+        assert_eq!(launch.query.as_deref(), Some("rust traits"));
+        assert_eq!(launch.paths, [absolute(Path::new("/tmp/notes.md"))]);
+
+        let launch = LaunchArgs::parse(&args(&["--query=borrow checker"]));
+        assert_eq!(launch.query.as_deref(), Some("borrow checker"));
+        assert!(launch.paths.is_empty());
+    }
+
+    #[test]
+    fn treats_a_flag_only_launch_as_empty() {
+        assert!(LaunchArgs::parse(&args(&["--minimized", "-psn_0_1"])).is_empty());
+        assert!(LaunchArgs::parse(&args(&["--query"])).is_empty());
+    }
+}
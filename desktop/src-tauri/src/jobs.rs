@@ -0,0 +1,462 @@
+//! History of the ingests the shell starts, with `ingest-progress` events as their status changes.
+//!
+//! `/ingest` only answers once it is done, so a job is split into one request per source (or per
+//! batch of paths) and progress is reported after each of them.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Local;
+use context_cache_desktop_lib::client::{BackendClient, Method};
+use context_cache_desktop_lib::dto::{IngestRequest, IngestResponse, IngestStats};
+use context_cache_desktop_lib::settings;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::outbox::{self, Sent};
+use crate::tray;
+
+const FILE_NAME: &str = "ingest_jobs.json";
+/// Finished jobs kept on disk.
+const MAX_JOBS: usize = 50;
+/// Failed files kept per job; `stats.failed` still counts all of them.
+const MAX_FAILED_FILES: usize = 200;
+const PATH_BATCH: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    /// The rest of the job waits in the outbox for the backend to come back.
+    Queued,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedFile {
+    pub path: String,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestJob {
+    pub id: String,
+    /// "All sources", a source label or "12 files".
+    pub label: String,
+    /// Source ids asked for; empty for all sources and for path ingests.
+    pub sources: Vec<String>,
+    pub path_count: usize,
+    pub status: JobStatus,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub stats: IngestStats,
+    pub failed_files: Vec<FailedFile>,
+    pub error: Option<String>,
+    /// Backend `ingest_jobs` ids, one per request the job sent.
+    pub backend_job_ids: Vec<String>,
+    /// Outbox operations still carrying part of this job.
+    pub queued_operations: Vec<String>,
+    pub steps_done: usize,
+    pub steps_total: usize,
+}
+
+impl IngestJob {
+    fn add(&mut self, response: &IngestResponse) {
+        self.backend_job_ids.push(response.job_id.clone());
+        add_stats(&mut self.stats, &response.stats);
+        let room = MAX_FAILED_FILES.saturating_sub(self.failed_files.len());
+        self.failed_files.extend(
+            response
+                .results
+                .iter()
+                .filter(|result| result.status == "error")
+                .take(room)
+                .map(|result| FailedFile {
+                    path: result.path.clone(),
+                    detail: result.detail.clone(),
+                }),
+        );
+        self.steps_done += 1;
+    }
+
+    fn finish(&mut self, error: Option<String>) {
+        self.status = if error.is_some() {
+            JobStatus::Failed
+        } else {
+            JobStatus::Completed
+        };
+        self.error = error;
+        self.finished_at = Some(Local::now().to_rfc3339());
+    }
+}
+
+fn add_stats(total: &mut IngestStats, stats: &IngestStats) {
+    total.processed += stats.processed;
+    total.skipped += stats.skipped;
+    total.failed += stats.failed;
+    total.chunks += stats.chunks;
+}
+
+/// Recent jobs, newest first.
+pub struct JobLog {
+    path: PathBuf,
+    jobs: Mutex<Vec<IngestJob>>,
+}
+
+impl JobLog {
+    /// Reads the history; jobs that were running when the app quit are marked failed.
+    pub fn load() -> Self {
+        let path = settings::default_path().with_file_name(FILE_NAME);
+        let mut jobs: Vec<IngestJob> = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+                eprintln!("Failed to parse {}: {err}; starting empty", path.display());
+                Vec::new()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                eprintln!("Failed to read {}: {err}; starting empty", path.display());
+                Vec::new()
+            }
+        };
+        for job in &mut jobs {
+            if job.status == JobStatus::Running {
+                job.finish(Some("Interrupted when the app quit".into()));
+            }
+        }
+        Self {
+            path,
+            jobs: Mutex::new(jobs),
+        }
+    }
+
+    fn save(&self, jobs: &[IngestJob]) {
+        let write = || -> io::Result<()> {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_vec_pretty(jobs)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let tmp = self.path.with_extension("json.tmp");
+            fs::write(&tmp, json)?;
+            fs::rename(&tmp, &self.path)
+        };
+        if let Err(err) = write() {
+            eprintln!("Failed to write {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// Adds `job` as the newest entry, dropping the oldest finished ones beyond [`MAX_JOBS`].
+fn insert(app: &AppHandle, job: IngestJob) {
+    let log = app.state::<JobLog>();
+    let mut jobs = log.jobs.lock().unwrap();
+    jobs.insert(0, job.clone());
+    while jobs.len() > MAX_JOBS {
+        // Unfinished jobs still expect updates, so only finished ones make room.
+        let Some(oldest) = jobs
+            .iter()
+            .rposition(|job| matches!(job.status, JobStatus::Completed | JobStatus::Failed))
+        else {
+            break;
+        };
+        jobs.remove(oldest);
+    }
+    log.save(&jobs);
+    drop(jobs);
+    progress(app, &job);
+}
+
+/// Applies `change` to the job with `id`, saves and reports it.
+fn update(app: &AppHandle, id: &str, change: impl FnOnce(&mut IngestJob)) {
+    let log = app.state::<JobLog>();
+    let mut jobs = log.jobs.lock().unwrap();
+    let Some(job) = jobs.iter_mut().find(|job| job.id == id) else {
+        return;
+    };
+    change(job);
+    let job = job.clone();
+    log.save(&jobs);
+    drop(jobs);
+    progress(app, &job);
+}
+
+fn progress(app: &AppHandle, job: &IngestJob) {
+    if let Err(err) = app.emit("ingest-progress", job) {
+        eprintln!("Failed to emit ingest-progress: {err}");
+    }
+}
+
+/// Runs `request` as a tracked job and returns the job's id with the backend's answer.
+///
+/// The answer of a job split into several requests sums up their stats and results and carries
+/// the backend job id of the last one.
+pub fn run(
+    app: &AppHandle,
+    client: &BackendClient,
+    request: &IngestRequest,
+) -> Result<(String, Sent<IngestResponse>), String> {
+    let steps = plan(app, client, request);
+    let mut id = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut id);
+    let id: String = id.iter().map(|byte| format!("{byte:02x}")).collect();
+    insert(
+        app,
+        IngestJob {
+            id: id.clone(),
+            label: describe(app, request),
+            sources: request
+                .sources
+                .clone()
+                .filter(|_| !request.all)
+                .unwrap_or_default(),
+            path_count: request.paths.as_ref().map_or(0, Vec::len),
+            status: JobStatus::Running,
+            started_at: Local::now().to_rfc3339(),
+            finished_at: None,
+            stats: IngestStats::default(),
+            failed_files: Vec::new(),
+            error: None,
+            backend_job_ids: Vec::new(),
+            queued_operations: Vec::new(),
+            steps_done: 0,
+            steps_total: steps.len(),
+        },
+    );
+    let mut combined: Option<IngestResponse> = None;
+    for (index, step) in steps.iter().enumerate() {
+        match send(app, client, step) {
+            Ok(Sent::Done(response)) => {
+                update(app, &id, |job| job.add(&response));
+                combined = Some(match combined.take() {
+                    None => response,
+                    Some(mut total) => {
+                        add_stats(&mut total.stats, &response.stats);
+                        total.results.extend(response.results);
+                        total.job_id = response.job_id;
+                        total
+                    }
+                });
+            }
+            Ok(Sent::Queued(operation)) => {
+                // The backend went away mid-job; the rest follows as one more queued request.
+                let mut queued = vec![operation];
+                if let Some(rest) = merge(&steps[index + 1..]) {
+                    match send(app, client, &rest) {
+                        Ok(Sent::Queued(operation)) => queued.push(operation),
+                        Ok(Sent::Done(response)) => update(app, &id, |job| job.add(&response)),
+                        Err(err) => eprintln!("Failed to queue the rest of ingest {id}: {err}"),
+                    }
+                }
+                update(app, &id, |job| {
+                    job.status = JobStatus::Queued;
+                    job.queued_operations = queued.clone();
+                });
+                return Ok((id, Sent::Queued(queued.remove(0))));
+            }
+            Err(err) => {
+                update(app, &id, |job| job.finish(Some(err.clone())));
+                return Err(err);
+            }
+        }
+    }
+    update(app, &id, |job| job.finish(None));
+    let response = combined.ok_or("The ingest had nothing to send")?;
+    Ok((id, Sent::Done(response)))
+}
+
+fn send(
+    app: &AppHandle,
+    client: &BackendClient,
+    request: &IngestRequest,
+) -> Result<Sent<IngestResponse>, String> {
+    let body = serde_json::to_value(request).map_err(|e| e.to_string())?;
+    outbox::send(app, client, Method::Post, "/ingest", Some(body))
+}
+
+/// Splits `request` into the requests the job sends one after another; never empty.
+///
+/// The backend ingests `all` source by source anyway, so per-source requests do the same work.
+fn plan(app: &AppHandle, client: &BackendClient, request: &IngestRequest) -> Vec<IngestRequest> {
+    // Queued work stays in one piece, so the pending list shows it as one operation.
+    if !app.state::<outbox::Outbox>().is_empty() {
+        return vec![request.clone()];
+    }
+    let sources = match request
+        .sources
+        .as_ref()
+        .filter(|sources| !request.all && !sources.is_empty())
+    {
+        Some(sources) => Some(sources.clone()),
+        None if request
+            .paths
+            .as_ref()
+            .is_some_and(|paths| !paths.is_empty()) =>
+        {
+            None
+        }
+        None => match client.list_sources() {
+            Ok(listing) if !listing.is_empty() => {
+                Some(listing.into_iter().map(|source| source.id).collect())
+            }
+            _ => return vec![request.clone()],
+        },
+    };
+    split(request, sources)
+}
+
+/// One request per batch of [`PATH_BATCH`] paths, or else one per source in `sources`.
+fn split(request: &IngestRequest, sources: Option<Vec<String>>) -> Vec<IngestRequest> {
+    if let Some(paths) = request.paths.as_ref().filter(|paths| !paths.is_empty()) {
+        return paths
+            .chunks(PATH_BATCH)
+            .map(|batch| IngestRequest {
+                paths: Some(batch.to_vec()),
+                ..IngestRequest::default()
+            })
+            .collect();
+    }
+    match sources {
+        Some(sources) if !sources.is_empty() => sources
+            .into_iter()
+            .map(|source| IngestRequest {
+                sources: Some(vec![source]),
+                ..IngestRequest::default()
+            })
+            .collect(),
+        _ => vec![request.clone()],
+    }
+}
+
+/// Joins the steps that have not run into a single request.
+fn merge(steps: &[IngestRequest]) -> Option<IngestRequest> {
+    if steps.is_empty() {
+        return None;
+    }
+    let join = |field: fn(&IngestRequest) -> Option<&Vec<String>>| {
+        let items: Vec<String> = steps.iter().filter_map(field).flatten().cloned().collect();
+        (!items.is_empty()).then_some(items)
+    };
+    Some(IngestRequest {
+        sources: join(|step| step.sources.as_ref()),
+        paths: join(|step| step.paths.as_ref()),
+        all: steps.iter().any(|step| step.all),
+    })
+}
+
+fn describe(app: &AppHandle, request: &IngestRequest) -> String {
+    let plural =
+        |count: usize, noun: &str| format!("{count} {noun}{}", if count == 1 { "" } else { "s" });
+    if let Some(paths) = request.paths.as_ref().filter(|paths| !paths.is_empty()) {
+        return plural(paths.len(), "file");
+    }
+    match request.sources.as_deref() {
+        Some([id]) if !request.all => {
+            tray::source_label(app, id).unwrap_or_else(|| format!("Source {id}"))
+        }
+        Some(sources) if !request.all && !sources.is_empty() => plural(sources.len(), "source"),
+        _ => "All sources".into(),
+    }
+}
+
+/// Records the outcome of a queued part once the outbox has sent it; returns the job's id.
+pub fn operation_finished(
+    app: &AppHandle,
+    operation_id: &str,
+    result: &Result<IngestResponse, String>,
+) -> Option<String> {
+    let job_id = {
+        let log = app.state::<JobLog>();
+        let jobs = log.jobs.lock().unwrap();
+        jobs.iter()
+            .find(|job| job.queued_operations.iter().any(|id| id == operation_id))
+            .map(|job| job.id.clone())
+    }?;
+    update(app, &job_id, |job| {
+        job.queued_operations.retain(|id| id != operation_id);
+        match result {
+            Ok(response) => job.add(response),
+            Err(error) => job.error = Some(error.clone()),
+        }
+        if job.queued_operations.is_empty() {
+            let error = job.error.take();
+            job.finish(error);
+        }
+    });
+    Some(job_id)
+}
+
+#[tauri::command]
+pub fn list_ingest_jobs(log: State<'_, JobLog>) -> Vec<IngestJob> {
+    log.jobs.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn get_ingest_job(log: State<'_, JobLog>, id: String) -> Result<IngestJob, String> {
+    log.jobs
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|job| job.id == id)
+        .cloned()
+        .ok_or_else(|| format!("No ingest job {id}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|index| format!("/notes/{index}.md"))
+            .collect()
+    }
+
+    #[test]
+    fn splits_paths_into_batches() {
+        let request = IngestRequest {
+            paths: Some(paths(PATH_BATCH * 2 + 1)),
+            ..IngestRequest::default()
+        };
+        let steps = split(&request, None);
+        assert_eq!(
+            steps
+                .iter()
+                .map(|step| step.paths.as_ref().unwrap().len())
+                .collect::<Vec<_>>(),
+            [PATH_BATCH, PATH_BATCH, 1]
+        );
+        assert!(steps.iter().all(|step| step.sources.is_none() && !step.all));
+    }
+
+    #[test]
+    fn splits_sources_one_per_request() {
+        let steps = split(&IngestRequest::all(), Some(vec!["a".into(), "b".into()]));
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].sources.as_deref(), Some(&["b".to_string()][..]));
+        assert!(!steps[1].all);
+    }
+
+    #[test]
+    fn keeps_a_request_whole_without_sources_to_split_by() {
+        let steps = split(&IngestRequest::all(), None);
+        assert_eq!(steps.len(), 1);
+        assert!(steps[0].all);
+        assert_eq!(split(&IngestRequest::all(), Some(Vec::new())).len(), 1);
+    }
+
+    #[test]
+    fn merges_the_steps_left_over() {
+        let steps = split(
+            &IngestRequest::default(),
+            Some(vec!["a".into(), "b".into(), "c".into()]),
+        );
+        let rest = merge(&steps[1..]).unwrap();
+        assert_eq!(rest.sources, Some(vec!["b".to_string(), "c".to_string()]));
+        assert_eq!(rest.paths, None);
+        assert!(merge(&steps[3..]).is_none());
+    }
+}
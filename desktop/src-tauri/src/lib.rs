@@ -0,0 +1,11 @@
+//! Shared building blocks for the Context Cache desktop shell.
+
+pub mod api_token;
+pub mod breaker;
+pub mod client;
+pub mod deep_link;
+pub mod dto;
+pub mod globs;
+pub mod schedule;
+pub mod settings;
+pub mod tls;
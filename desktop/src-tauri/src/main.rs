@@ -1,21 +1,33 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use serde::Serialize;
+mod add_source;
+mod app_settings;
+mod cli;
+mod clippings;
+mod conditions;
+mod drop_ingest;
+mod health;
+mod ingest;
+mod instance;
+mod jobs;
+mod navigation;
+mod outbox;
+mod pause;
+mod quick_search;
+mod scheduler;
+mod shortcuts;
+mod sidecar;
+mod sources;
+mod tray;
+mod watcher;
+
+use context_cache_desktop_lib::client::{BackendClient, Method};
+use context_cache_desktop_lib::dto::{QueryFilters, QueryResponse, WhyResponse};
 use serde_json::Value;
+use sidecar::Sidecar;
 use std::env;
-use tauri::{
-    menu::{MenuBuilder, MenuEvent, MenuItemBuilder},
-    tray::TrayIconBuilder,
-    AppHandle, Emitter, Manager,
-};
-use tauri::image::Image;
-
-const DEFAULT_HOST: &str = "http://127.0.0.1:5173";
-
-#[derive(Serialize, Clone)]
-struct UiNotification<'a> {
-    message: &'a str,
-}
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager, RunEvent};
 
 #[tauri::command]
 fn open_ui(app_handle: AppHandle) -> Result<(), String> {
@@ -28,88 +40,155 @@ fn open_ui(app_handle: AppHandle) -> Result<(), String> {
     }
 }
 
-#[tauri::command]
-fn trigger_ingest(app_handle: AppHandle) -> Result<(), String> {
-    let payload = serde_json::json!({ "all": true });
-    call_backend("/ingest", "POST", Some(&payload))?;
-    app_handle
-        .emit(
-            "ingest-finished",
-            UiNotification {
-                message: "Ingest triggered",
-            },
-        )
-        .map_err(|e| e.to_string())?;
-    Ok(())
-}
+/// Backend client built from the current settings; replaced when they change.
+struct ActiveClient(RwLock<BackendClient>);
 
-fn call_backend(path: &str, method: &str, body: Option<&Value>) -> Result<(), String> {
-    let host = env::var("CTXC_HOST").unwrap_or_else(|_| DEFAULT_HOST.to_string());
-    let url = format!("{}{}", host.trim_end_matches('/'), path);
-    let response = match method {
-        "POST" => {
-            let request = ureq::post(&url);
-            if let Some(payload) = body {
-                request.send_json(payload.clone())
-            } else {
-                request.call()
-            }
-        }
-        "GET" => ureq::get(&url).call(),
-        _ => return Err(format!("Unsupported method {method}")),
-    };
-
-    response
-        .map(|_| ())
-        .map_err(|err| format!("Request to {url} failed: {err}"))
+fn client(app: &AppHandle) -> BackendClient {
+    app.state::<ActiveClient>().0.read().unwrap().clone()
 }
 
-fn init_tray(app: &AppHandle) -> tauri::Result<()> {
-    let open_item = MenuItemBuilder::with_id("open", "Open UI").build(app)?;
-    let ingest_item = MenuItemBuilder::with_id("ingest", "Ingest Now").build(app)?;
-    let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
+fn set_client(app: &AppHandle, client: BackendClient) {
+    *app.state::<ActiveClient>().0.write().unwrap() = client;
+}
 
-    let menu = MenuBuilder::new(app)
-        .item(&open_item)
-        .item(&ingest_item)
-        .item(&quit_item)
-        .build()?;
+/// Runs blocking backend I/O on the async runtime's blocking pool.
+async fn blocking<T, F>(task: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| e.to_string())?
+}
 
-    let tray_icon = Image::from_bytes(include_bytes!("../icons/tray.png"))?;
+#[tauri::command]
+async fn query(
+    app_handle: AppHandle,
+    query: String,
+    k: Option<u32>,
+    rerank: Option<bool>,
+    hybrid: Option<bool>,
+    filters: Option<QueryFilters>,
+) -> Result<QueryResponse, String> {
+    if query.trim().is_empty() {
+        return Err("Query must not be empty".into());
+    }
+    let request =
+        app_settings::current(&app_handle).query_request(query, k, rerank, hybrid, filters);
+    let client = client(&app_handle);
+    blocking(move || Ok(client.query(&request)?)).await
+}
 
-    let tray = TrayIconBuilder::new()
-        .icon(tray_icon)
-        .menu(&menu)
-        .on_menu_event(|app, event: MenuEvent| match event.id().as_ref() {
-            "open" => {
-                if let Err(err) = open_ui(app.clone()) {
-                    eprintln!("Failed to open UI: {err}");
-                }
-            }
-            "ingest" => {
-                if let Err(err) = trigger_ingest(app.clone()) {
-                    eprintln!("Failed to ingest: {err}");
-                }
-            }
-            "quit" => {
-                app.exit(0);
-            }
-            _ => {}
-        })
-        .build(app)?;
+#[tauri::command]
+async fn why(app_handle: AppHandle, query_id: String) -> Result<WhyResponse, String> {
+    let client = client(&app_handle);
+    blocking(move || Ok(client.why(&query_id)?)).await
+}
 
-    app.manage(tray);
-    Ok(())
+/// Forwards a UI request to the backend, so the webview never handles the API token.
+///
+/// Mutations are queued in the outbox while the backend is down.
+#[tauri::command]
+async fn backend_request(
+    app_handle: AppHandle,
+    method: Method,
+    path: String,
+    body: Option<Value>,
+) -> Result<Value, String> {
+    if !path.starts_with('/') {
+        return Err(format!("Backend path must start with '/': {path}"));
+    }
+    let client = client(&app_handle);
+    blocking(move || {
+        if outbox::is_mutation(method, &path) {
+            return outbox::send(&app_handle, &client, method, &path, body)?.done();
+        }
+        Ok(client.send(method, &path, body.as_ref())?)
+    })
+    .await
 }
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if cli::is_command(&args) {
+        std::process::exit(cli::run(&args));
+    }
+    let primary = match instance::acquire() {
+        instance::Instance::Primary(primary) => primary,
+        instance::Instance::Secondary => {
+            if !instance::forward(&args) {
+                eprintln!("Context Cache is already running but did not accept the arguments");
+            }
+            return;
+        }
+    };
+
     tauri::Builder::default()
-        .setup(|app| {
-            let handle = app.handle();
-            init_tray(&handle)?;
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_opener::init())
+        .manage(add_source::PendingFolder::default())
+        .manage(ingest::IngestTracker::default())
+        .manage(outbox::Outbox::load())
+        .manage(jobs::JobLog::load())
+        .setup(move |app| {
+            let settings_state = app_settings::SettingsState::load();
+            let settings = settings_state.effective();
+            app.manage(settings_state);
+            let client = BackendClient::from_settings(&settings);
+            if settings.sidecar.enabled {
+                let api_token = settings.local_token().map(str::to_string);
+                app.manage(Sidecar::spawn(settings.sidecar, api_token));
+            }
+            app.manage(ActiveClient(RwLock::new(client)));
+            tray::init_tray(app.handle())?;
+            pause::init(app.handle());
+            quick_search::init(app.handle())?;
+            clippings::init(app.handle());
+            navigation::init(app.handle());
+            drop_ingest::init(app.handle());
+            watcher::init(app.handle());
+            instance::listen(app.handle(), primary);
+            instance::handle(app.handle(), instance::LaunchArgs::parse(&args));
+            health::start(app.handle());
+            scheduler::start(app.handle());
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![open_ui, trigger_ingest])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(tauri::generate_handler![
+            open_ui,
+            ingest::trigger_ingest,
+            query,
+            why,
+            backend_request,
+            sources::list_sources,
+            sources::add_source,
+            sources::update_source,
+            sources::remove_source,
+            add_source::get_source_draft,
+            add_source::submit_source_draft,
+            health::backend_status,
+            app_settings::get_settings,
+            app_settings::set_settings,
+            app_settings::switch_profile,
+            quick_search::hide_quick_search,
+            quick_search::activate_result,
+            navigation::take_pending_navigation,
+            outbox::list_pending_operations,
+            outbox::cancel_pending_operation,
+            jobs::list_ingest_jobs,
+            jobs::get_ingest_job
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                if let Some(sidecar) = app.try_state::<Sidecar>() {
+                    sidecar.shutdown();
+                }
+            }
+        });
 }
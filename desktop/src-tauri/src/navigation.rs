@@ -0,0 +1,87 @@
+//! Routes `ctxc://` links and search requests to the main window.
+
+use std::sync::Mutex;
+
+use context_cache_desktop_lib::deep_link::DeepLink;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::open_ui;
+
+/// UI route a link resolves to, plus the parsed link for pages that want the ids.
+#[derive(Serialize, Clone)]
+pub struct Navigation {
+    path: String,
+    link: Option<DeepLink>,
+}
+
+/// Last link received, kept until the UI has loaded and picked it up.
+#[derive(Default)]
+pub struct PendingNavigation(Mutex<Option<Navigation>>);
+
+pub fn init(app: &AppHandle) {
+    app.manage(PendingNavigation::default());
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(err) = app.deep_link().register_all() {
+        eprintln!("Failed to register the ctxc:// scheme: {err}");
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_link(&handle, url.as_str());
+        }
+    });
+    // Links passed on the command line (Linux/Windows) arrive through `instance`.
+}
+
+/// Routes the main window to what `text` points at.
+pub fn handle_link(app: &AppHandle, text: &str) {
+    let link = match DeepLink::parse(text) {
+        Ok(link) => link,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+    navigate(app, route(&link), Some(link));
+}
+
+/// Focuses the main window and sends it to `path`.
+pub fn navigate(app: &AppHandle, path: String, link: Option<DeepLink>) {
+    let navigation = Navigation { path, link };
+    *app.state::<PendingNavigation>().0.lock().unwrap() = Some(navigation.clone());
+    if let Err(err) = open_ui(app.clone()) {
+        eprintln!("Failed to open UI: {err}");
+    }
+    if let Err(err) = app.emit("navigate", &navigation) {
+        eprintln!("Failed to emit navigate: {err}");
+    }
+}
+
+fn route(link: &DeepLink) -> String {
+    let encode = |id: &str| utf8_percent_encode(id, NON_ALPHANUMERIC).to_string();
+    match link {
+        DeepLink::Query { query_id } => format!("/why/{}", encode(query_id)),
+        DeepLink::Document {
+            document_id,
+            chunk_id: Some(chunk_id),
+        } => format!(
+            "/search?document={}&chunk={}",
+            encode(document_id),
+            encode(chunk_id)
+        ),
+        DeepLink::Document {
+            document_id,
+            chunk_id: None,
+        } => format!("/search?document={}", encode(document_id)),
+        DeepLink::Chunk { chunk_id } => format!("/search?chunk={}", encode(chunk_id)),
+    }
+}
+
+/// Hands the UI the link that opened or focused the app, clearing it.
+#[tauri::command]
+pub fn take_pending_navigation(pending: State<'_, PendingNavigation>) -> Option<Navigation> {
+    pending.0.lock().unwrap().take()
+}
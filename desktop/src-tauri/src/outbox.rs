@@ -0,0 +1,397 @@
+//! Durable queue of mutating requests made while the backend is down, replayed in order later.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+
+use context_cache_desktop_lib::client::{BackendClient, Method};
+use context_cache_desktop_lib::dto::{IngestRequest, IngestResponse};
+use context_cache_desktop_lib::settings;
+use percent_encoding::percent_decode_str;
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::{app_settings, ingest, jobs, pause, sources};
+
+const FILE_NAME: &str = "outbox.json";
+
+pub const QUEUED_MESSAGE: &str =
+    "The backend is unreachable; the request was queued and will be sent once it is back";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub id: String,
+    /// What the pending list shows, e.g. "Ingest all sources".
+    pub label: String,
+    /// The backend profile it was meant for; it is only replayed while that profile is active.
+    pub profile: String,
+    pub method: Method,
+    pub path: String,
+    pub body: Option<Value>,
+    pub queued_at: String,
+}
+
+pub struct Outbox {
+    path: PathBuf,
+    pending: Mutex<Vec<Operation>>,
+    /// Held while replaying, so overlapping health polls never send an operation twice.
+    replaying: Mutex<()>,
+}
+
+/// What [`send`] did with a request.
+pub enum Sent<T> {
+    Done(T),
+    /// Queued as the operation with this id.
+    Queued(String),
+}
+
+impl<T> Sent<T> {
+    /// The response, or [`QUEUED_MESSAGE`] as an error for callers that need one now.
+    pub fn done(self) -> Result<T, String> {
+        match self {
+            Sent::Done(value) => Ok(value),
+            Sent::Queued(_) => Err(QUEUED_MESSAGE.into()),
+        }
+    }
+}
+
+impl Outbox {
+    /// Reads the queue left by the previous run; a corrupt file is logged and ignored.
+    pub fn load() -> Self {
+        Self::load_from(settings::default_path().with_file_name(FILE_NAME))
+    }
+
+    fn load_from(path: PathBuf) -> Self {
+        let pending = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+                eprintln!("Failed to parse {}: {err}; starting empty", path.display());
+                Vec::new()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                eprintln!("Failed to read {}: {err}; starting empty", path.display());
+                Vec::new()
+            }
+        };
+        Self {
+            path,
+            pending: Mutex::new(pending),
+            replaying: Mutex::new(()),
+        }
+    }
+
+    pub fn pending(&self) -> Vec<Operation> {
+        self.pending.lock().unwrap().clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.lock().unwrap().is_empty()
+    }
+
+    fn next_for(&self, profile: &str) -> Option<Operation> {
+        let pending = self.pending.lock().unwrap();
+        pending
+            .iter()
+            .find(|operation| operation.profile == profile)
+            .cloned()
+    }
+
+    fn push(&self, operation: Operation) -> Result<(), String> {
+        let mut pending = self.pending.lock().unwrap();
+        pending.push(operation);
+        self.save(&pending)
+    }
+
+    /// False when the operation was already sent or cancelled.
+    fn remove(&self, id: &str) -> Result<bool, String> {
+        let mut pending = self.pending.lock().unwrap();
+        let before = pending.len();
+        pending.retain(|operation| operation.id != id);
+        if pending.len() == before {
+            return Ok(false);
+        }
+        self.save(&pending).map(|_| true)
+    }
+
+    /// Writes atomically, like the settings file, so a crash never loses the whole queue.
+    fn save(&self, pending: &[Operation]) -> Result<(), String> {
+        let write = || -> io::Result<()> {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_vec_pretty(pending)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let tmp = self.path.with_extension("json.tmp");
+            fs::write(&tmp, json)?;
+            fs::rename(&tmp, &self.path)
+        };
+        write().map_err(|err| format!("Failed to write {}: {err}", self.path.display()))
+    }
+}
+
+/// Requests that change backend state; `/query` is a POST but only reads.
+pub fn is_mutation(method: Method, path: &str) -> bool {
+    !matches!(method, Method::Get | Method::Options) && path != "/query"
+}
+
+/// Sends a mutating request, or queues it while the backend is down or older requests still wait.
+///
+/// Only requests that never reached the backend are queued; after a timeout or a 502 the
+/// backend may have applied it, so the error is returned rather than risking a duplicate.
+pub fn send<T: DeserializeOwned>(
+    app: &AppHandle,
+    client: &BackendClient,
+    method: Method,
+    path: &str,
+    body: Option<Value>,
+) -> Result<Sent<T>, String> {
+    let outbox = app.state::<Outbox>();
+    let profile = app_settings::current(app).active().name.clone();
+    if outbox.next_for(&profile).is_none() {
+        match client.send(method, path, body.as_ref()) {
+            Err(err) if err.is_unsent() => {}
+            result => return result.map(Sent::Done).map_err(String::from),
+        }
+    }
+    let mut id = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut id);
+    let id: String = id.iter().map(|byte| format!("{byte:02x}")).collect();
+    let operation = Operation {
+        id: id.clone(),
+        label: label(method, path, body.as_ref()),
+        profile,
+        method,
+        path: path.to_string(),
+        body,
+        queued_at: chrono::Local::now().to_rfc3339(),
+    };
+    outbox.push(operation)?;
+    changed(app);
+    Ok(Sent::Queued(id))
+}
+
+/// Starts sending queued operations unless a replay is already running.
+pub fn spawn_replay(app: &AppHandle) {
+    if app.state::<Outbox>().is_empty() {
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || replay(&app));
+}
+
+/// Sends the active profile's queued operations oldest first and stops at the first sign the
+/// backend is down again. Operations for other profiles wait until theirs is active.
+fn replay(app: &AppHandle) {
+    let outbox = app.state::<Outbox>();
+    let Ok(_replaying) = outbox.replaying.try_lock() else {
+        return;
+    };
+    let client = crate::client(app);
+    let profile = app_settings::current(app).active().name.clone();
+    loop {
+        // Resuming indexing starts the replay again.
+        if pause::is_paused(app) {
+            return;
+        }
+        let Some(operation) = outbox.next_for(&profile) else {
+            return;
+        };
+        // Held until the outcome is reported, like the claim of an ingest started directly.
+        let _in_flight = match claim(app, &operation) {
+            Ok(in_flight) => in_flight,
+            // Another ingest of the same sources is running; the next health poll tries again.
+            Err(_) => return,
+        };
+        let result =
+            client.send::<_, Value>(operation.method, &operation.path, operation.body.as_ref());
+        // Anything that may have reached the backend is settled now, never sent a second time.
+        if matches!(&result, Err(err) if err.is_unsent()) {
+            return;
+        }
+        // A cancel that raced the send finds nothing to remove; the outcome is still reported.
+        if let Err(err) = outbox.remove(&operation.id) {
+            eprintln!("{err}");
+            return;
+        }
+        changed(app);
+        finished(app, &operation, result.map_err(String::from));
+    }
+}
+
+/// Claims the sources of a queued ingest, so it never overlaps another ingest of them.
+fn claim(app: &AppHandle, operation: &Operation) -> Result<Option<ingest::InFlight>, String> {
+    if operation.path != "/ingest" {
+        return Ok(None);
+    }
+    // A body that is not an ingest request claims nothing; the backend will reject it.
+    let body = operation.body.clone().unwrap_or_default();
+    let Ok(request) = serde_json::from_value::<IngestRequest>(body) else {
+        return Ok(None);
+    };
+    ingest::claim(app, &request).map(Some)
+}
+
+fn finished(app: &AppHandle, operation: &Operation, result: Result<Value, String>) {
+    if operation.path == "/ingest" {
+        let result = result.and_then(|value| {
+            serde_json::from_value::<IngestResponse>(value).map_err(|e| e.to_string())
+        });
+        let job_id = jobs::operation_finished(app, &operation.id, &result);
+        let reported = match result {
+            Ok(response) => ingest::report_finished(app, job_id.as_deref(), &response),
+            Err(error) => ingest::report_failed(app, &error),
+        };
+        if let Err(err) = reported {
+            eprintln!("Failed to report queued ingest: {err}");
+        }
+        return;
+    }
+    if operation.path.starts_with("/sources") {
+        sources::sources_changed(app);
+    }
+    if let Err(error) = result {
+        eprintln!("Queued request \"{}\" failed: {error}", operation.label);
+        ingest::notify(
+            app,
+            "Queued request failed",
+            &format!("{}: {error}", operation.label),
+        );
+    }
+}
+
+fn changed(app: &AppHandle) {
+    let pending = app.state::<Outbox>().pending();
+    if let Err(err) = app.emit("outbox-changed", &pending) {
+        eprintln!("Failed to emit outbox-changed: {err}");
+    }
+}
+
+fn label(method: Method, path: &str, body: Option<&Value>) -> String {
+    let field = |name: &str| body.and_then(|body| body.get(name));
+    let count = |name: &str| field(name).and_then(Value::as_array).map_or(0, Vec::len);
+    let plural =
+        |count: usize, noun: &str| format!("{count} {noun}{}", if count == 1 { "" } else { "s" });
+    if let Some(id) = path.strip_prefix("/sources/") {
+        let id = percent_decode_str(id).decode_utf8_lossy();
+        match method {
+            Method::Patch => return format!("Edit source {id}"),
+            Method::Delete => return format!("Remove source {id}"),
+            _ => {}
+        }
+    }
+    match (method, path) {
+        (Method::Post, "/ingest") if count("paths") > 0 => {
+            format!("Ingest {}", plural(count("paths"), "path"))
+        }
+        (Method::Post, "/ingest") if count("sources") > 0 => {
+            format!("Ingest {}", plural(count("sources"), "source"))
+        }
+        (Method::Post, "/ingest") => "Ingest all sources".into(),
+        (Method::Post, "/tags/upsert") => "Update tags".into(),
+        (Method::Post, "/delete") => "Delete documents".into(),
+        (Method::Post, "/sources") => match field("label").or(field("uri")).and_then(Value::as_str)
+        {
+            Some(name) => format!("Add source \"{name}\""),
+            None => "Add source".into(),
+        },
+        _ => format!("{} {path}", method.as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn operation(id: &str, profile: &str) -> Operation {
+        Operation {
+            id: id.into(),
+            label: "Ingest all sources".into(),
+            profile: profile.into(),
+            method: Method::Post,
+            path: "/ingest".into(),
+            body: Some(json!({ "all": true })),
+            queued_at: "2026-01-01T00:00:00Z".into(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_queue_across_restarts() {
+        let dir = env::temp_dir().join(format!("ctxc-outbox-{}", std::process::id()));
+        let path = dir.join(FILE_NAME);
+        let outbox = Outbox::load_from(path.clone());
+        outbox.push(operation("a", "default")).unwrap();
+        outbox.push(operation("b", "work")).unwrap();
+        outbox.push(operation("c", "default")).unwrap();
+        assert!(outbox.remove("a").unwrap());
+        assert!(!outbox.remove("a").unwrap());
+
+        let reloaded = Outbox::load_from(path);
+        let ids: Vec<_> = reloaded.pending().into_iter().map(|op| op.id).collect();
+        assert_eq!(ids, ["b", "c"]);
+        assert_eq!(reloaded.next_for("default").unwrap().id, "c");
+        assert_eq!(reloaded.next_for("work").unwrap().id, "b");
+        assert!(reloaded.next_for("other").is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn queues_only_requests_that_change_state() {
+        assert!(is_mutation(Method::Post, "/ingest"));
+        assert!(is_mutation(Method::Delete, "/sources/abc"));
+        assert!(!is_mutation(Method::Post, "/query"));
+        assert!(!is_mutation(Method::Get, "/sources"));
+    }
+
+    #[test]
+    fn labels_operations_for_the_pending_list() {
+        let body = json!({ "paths": ["/a.md", "/b.md"] });
+        assert_eq!(
+            label(Method::Post, "/ingest", Some(&body)),
+            "Ingest 2 paths"
+        );
+        let body = json!({ "sources": ["abc"] });
+        assert_eq!(
+            label(Method::Post, "/ingest", Some(&body)),
+            "Ingest 1 source"
+        );
+        let body = json!({ "all": true });
+        assert_eq!(
+            label(Method::Post, "/ingest", Some(&body)),
+            "Ingest all sources"
+        );
+        assert_eq!(
+            label(Method::Delete, "/sources/a%2Fb", None),
+            "Remove source a/b"
+        );
+        let body = json!({ "uri": "/notes" });
+        assert_eq!(
+            label(Method::Post, "/sources", Some(&body)),
+            "Add source \"/notes\""
+        );
+        assert_eq!(label(Method::Post, "/other", None), "POST /other");
+    }
+}
+
+#[tauri::command]
+pub fn list_pending_operations(outbox: State<'_, Outbox>) -> Vec<Operation> {
+    outbox.pending()
+}
+
+#[tauri::command]
+pub fn cancel_pending_operation(app_handle: AppHandle, id: String) -> Result<(), String> {
+    if !app_handle.state::<Outbox>().remove(&id)? {
+        return Err("The operation was already sent or cancelled".into());
+    }
+    changed(&app_handle);
+    jobs::operation_finished(&app_handle, &id, &Err("Cancelled".into()));
+    Ok(())
+}
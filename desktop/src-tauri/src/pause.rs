@@ -0,0 +1,189 @@
+//! "Pause Indexing": holds scheduled ingests, the shell watcher and outbox replay until resumed.
+//!
+//! Manual ingests still run; pausing only quiets work the app starts on its own.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Days, Local, TimeZone};
+use context_cache_desktop_lib::settings;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{outbox, tray};
+
+const FILE_NAME: &str = "pause.json";
+const TICK: Duration = Duration::from_secs(15);
+
+/// How long a pause chosen from the tray lasts.
+#[derive(Debug, Clone, Copy)]
+pub enum PauseFor {
+    UntilResumed,
+    HalfHour,
+    Hour,
+    UntilTomorrow,
+}
+
+impl PauseFor {
+    /// Tray menu id suffix.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "30m" => Some(PauseFor::HalfHour),
+            "1h" => Some(PauseFor::Hour),
+            "tomorrow" => Some(PauseFor::UntilTomorrow),
+            _ => None,
+        }
+    }
+
+    fn until(self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            PauseFor::UntilResumed => None,
+            PauseFor::HalfHour => Some(now + chrono::Duration::minutes(30)),
+            PauseFor::Hour => Some(now + chrono::Duration::hours(1)),
+            PauseFor::UntilTomorrow => {
+                let midnight = now
+                    .date_naive()
+                    .checked_add_days(Days::new(1))?
+                    .and_hms_opt(0, 0, 0)?;
+                Local.from_local_datetime(&midnight).earliest()
+            }
+        }
+    }
+}
+
+/// On disk as `{"until": null}` for an open-ended pause; the file is absent when not paused.
+#[derive(Serialize, Deserialize)]
+struct Stored {
+    until: Option<String>,
+}
+
+/// Whether indexing is paused and, if so, when it resumes by itself (`Some(None)`: never).
+pub struct Pause {
+    path: PathBuf,
+    state: Mutex<Option<Option<DateTime<Local>>>>,
+}
+
+impl Pause {
+    fn load() -> Self {
+        let path = settings::default_path().with_file_name(FILE_NAME);
+        let state = match fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice::<Stored>(&bytes) {
+                Ok(stored) => Some(stored.until.and_then(|until| {
+                    DateTime::parse_from_rfc3339(&until)
+                        .ok()
+                        .map(|until| until.with_timezone(&Local))
+                })),
+                Err(err) => {
+                    eprintln!("Failed to parse {}: {err}; not paused", path.display());
+                    None
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => {
+                eprintln!("Failed to read {}: {err}; not paused", path.display());
+                None
+            }
+        };
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    fn store(&self, state: Option<Option<DateTime<Local>>>) {
+        *self.state.lock().unwrap() = state;
+        let write = || -> io::Result<()> {
+            let Some(until) = state else {
+                return match fs::remove_file(&self.path) {
+                    Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                    _ => Ok(()),
+                };
+            };
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let stored = Stored {
+                until: until.map(|until| until.to_rfc3339()),
+            };
+            let json = serde_json::to_vec_pretty(&stored)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let tmp = self.path.with_extension("json.tmp");
+            fs::write(&tmp, json)?;
+            fs::rename(&tmp, &self.path)
+        };
+        if let Err(err) = write() {
+            eprintln!("Failed to write {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// Restores a pause from the last session; call after the tray exists.
+pub fn init(app: &AppHandle) {
+    app.manage(Pause::load());
+    // A pause that ran out while the app was closed ends now.
+    if !is_paused(app) {
+        app.state::<Pause>().store(None);
+    }
+    show(app);
+    let app = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(TICK);
+        let expired = matches!(
+            *app.state::<Pause>().state.lock().unwrap(),
+            Some(Some(until)) if until <= Local::now()
+        );
+        if expired {
+            resume(&app);
+        }
+    });
+}
+
+pub fn is_paused(app: &AppHandle) -> bool {
+    match *app.state::<Pause>().state.lock().unwrap() {
+        Some(Some(until)) => Local::now() < until,
+        Some(None) => true,
+        None => false,
+    }
+}
+
+pub fn pause(app: &AppHandle, duration: PauseFor) {
+    let until = duration.until(Local::now());
+    app.state::<Pause>().store(Some(until));
+    show(app);
+}
+
+pub fn resume(app: &AppHandle) {
+    app.state::<Pause>().store(None);
+    show(app);
+    outbox::spawn_replay(app);
+}
+
+/// Toggles from the tray check item: pauses until resumed, or resumes.
+pub fn toggle(app: &AppHandle) {
+    if is_paused(app) {
+        resume(app);
+    } else {
+        pause(app, PauseFor::UntilResumed);
+    }
+}
+
+fn show(app: &AppHandle) {
+    let line = match *app.state::<Pause>().state.lock().unwrap() {
+        None => None,
+        Some(None) => Some("⏸ Indexing paused".to_string()),
+        Some(Some(until)) if until.date_naive() == Local::now().date_naive() => {
+            Some(format!("⏸ Indexing paused until {}", until.format("%H:%M")))
+        }
+        Some(Some(until)) => Some(format!(
+            "⏸ Indexing paused until {}",
+            until.format("%a %H:%M")
+        )),
+    };
+    if let Err(err) = tray::show_paused(app, line) {
+        eprintln!("Failed to update tray pause state: {err}");
+    }
+}
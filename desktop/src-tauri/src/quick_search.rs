@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+
+use context_cache_desktop_lib::dto::ChunkResult;
+use context_cache_desktop_lib::settings::{ProvenanceAction, Settings};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_opener::OpenerExt;
+use url::Url;
+
+use crate::app_settings;
+use crate::shortcuts::BoundShortcut;
+
+const WINDOW_LABEL: &str = "quick-search";
+
+/// The global shortcut currently bound to the popup.
+#[derive(Default)]
+pub struct QuickSearchShortcut(BoundShortcut);
+
+/// Builds the hidden popup and binds the configured shortcut.
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    app.manage(QuickSearchShortcut::default());
+    let window =
+        WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App("quick-search".into()))
+            .title("Quick Search")
+            .inner_size(640.0, 420.0)
+            .decorations(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .resizable(false)
+            .center()
+            .visible(false)
+            .build()?;
+    let handle = window.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::CloseRequested { api, .. } => {
+            api.prevent_close();
+            let _ = handle.hide();
+        }
+        WindowEvent::Focused(false) => {
+            let _ = handle.hide();
+        }
+        _ => {}
+    });
+    if let Err(err) = apply_shortcut(app, &app_settings::current(app)) {
+        eprintln!("{err}");
+    }
+    Ok(())
+}
+
+/// Rebinds the global shortcut when the configured one changed.
+pub fn apply_shortcut(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    app.state::<QuickSearchShortcut>().0.rebind(
+        app,
+        &settings.quick_search.shortcut,
+        "quick-search",
+        toggle,
+    )
+}
+
+fn toggle(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(WINDOW_LABEL) else {
+        return;
+    };
+    let result = if window.is_visible().unwrap_or(false) {
+        window.hide()
+    } else {
+        window
+            .center()
+            .and_then(|_| window.show())
+            .and_then(|_| window.set_focus())
+            .and_then(|_| window.emit("quick-search-shown", ()))
+    };
+    if let Err(err) = result {
+        eprintln!("Failed to toggle quick search: {err}");
+    }
+}
+
+#[tauri::command]
+pub fn hide_quick_search(app_handle: AppHandle) -> Result<(), String> {
+    if let Some(window) = app_handle.get_webview_window(WINDOW_LABEL) {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Opens or copies a result's provenance; `alternate` picks the action Enter does not.
+#[tauri::command]
+pub fn activate_result(
+    app_handle: AppHandle,
+    result: ChunkResult,
+    alternate: bool,
+) -> Result<ProvenanceAction, String> {
+    let mut action = app_settings::current(&app_handle).quick_search.enter_action;
+    if alternate {
+        action = action.other();
+    }
+    match action {
+        ProvenanceAction::Open => open_provenance(&app_handle, &result)?,
+        ProvenanceAction::Copy => copy_provenance(&app_handle, &result)?,
+    }
+    hide_quick_search(app_handle)?;
+    Ok(action)
+}
+
+/// Opens the document a chunk came from with the system's default handler.
+pub fn open_provenance(app: &AppHandle, result: &ChunkResult) -> Result<(), String> {
+    let target = provenance_str(result, "uri")
+        .or_else(|| provenance_str(result, "external_id"))
+        .ok_or_else(|| "This result has no source location".to_string())?;
+    if target.starts_with("http://") || target.starts_with("https://") {
+        return app
+            .opener()
+            .open_url(target, None::<&str>)
+            .map_err(|e| e.to_string());
+    }
+    app.opener()
+        .open_path(document_path(target).to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+/// Copies the chunk's deep link (`<document>#char=<start>-<end>`) to the clipboard.
+pub fn copy_provenance(app: &AppHandle, result: &ChunkResult) -> Result<(), String> {
+    let link = provenance_str(result, "deep_link")
+        .or_else(|| provenance_str(result, "external_id"))
+        .ok_or_else(|| "This result has no provenance link".to_string())?;
+    app.clipboard().write_text(link).map_err(|e| e.to_string())
+}
+
+fn provenance_str<'a>(result: &'a ChunkResult, key: &str) -> Option<&'a str> {
+    result
+        .provenance
+        .get(key)
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.is_empty())
+}
+
+/// Strips the `#<message index>` suffix mailbox documents carry in their external id.
+fn document_path(external_id: &str) -> PathBuf {
+    if let Some(path) = Url::parse(external_id)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok())
+    {
+        return path;
+    }
+    match external_id.rsplit_once('#') {
+        Some((file, _)) if !Path::new(external_id).exists() => PathBuf::from(file),
+        _ => PathBuf::from(external_id),
+    }
+}
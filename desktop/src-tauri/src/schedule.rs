@@ -0,0 +1,355 @@
+//! Ingest schedules: fixed intervals (`every 10m`) or five-field cron expressions (`0 2 * * *`).
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+
+/// How far ahead a cron expression is searched; covers leap-day schedules like `0 0 29 2 *`.
+const CRON_HORIZON_DAYS: u32 = 366 * 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Every(Duration),
+    Cron(Cron),
+}
+
+impl Schedule {
+    /// Accepts `every <n>m|h|d`, `@hourly`, `@daily`, `@weekly` or `minute hour day month weekday`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if let Some(interval) = text.strip_prefix("every") {
+            return parse_interval(interval.trim())
+                .map(Schedule::Every)
+                .ok_or_else(|| format!("\"{text}\" is not an interval like \"every 10m\""));
+        }
+        let expression = match text {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            other => other,
+        };
+        let cron = Cron::parse(expression).map_err(|err| format!("\"{text}\": {err}"))?;
+        if cron.next_after(Local::now()).is_none() {
+            return Err(format!("\"{text}\" never matches a date"));
+        }
+        Ok(Schedule::Cron(cron))
+    }
+
+    /// When the next run is due; at or before `now` means it is due already.
+    ///
+    /// Interval schedules that never ran are due at once. Cron schedules catch up on a single
+    /// missed run, e.g. when the machine was asleep at 02:00. A cron schedule without a
+    /// `last_run` is only due at its next match after `now`, so callers keep the time they
+    /// first saw it as its `last_run`; otherwise it never comes due.
+    pub fn next_run(
+        &self,
+        last_run: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        match self {
+            Schedule::Every(interval) => Some(last_run.map_or(now, |last| last + *interval)),
+            Schedule::Cron(cron) => cron.next_after(last_run.unwrap_or(now)),
+        }
+    }
+}
+
+fn parse_interval(text: &str) -> Option<Duration> {
+    let split = text
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(text.len());
+    let count: i64 = text[..split].parse().ok().filter(|count| *count > 0)?;
+    match text[split..].trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(Duration::minutes(count)),
+        "h" | "hour" | "hours" => Some(Duration::hours(count)),
+        "d" | "day" | "days" => Some(Duration::days(count)),
+        _ => None,
+    }
+}
+
+/// A cron expression as bit sets of the minutes, hours, days, months and weekdays it matches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Cron matches either day field when both are restricted, so remember which are `*`.
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Cron {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err("expected five fields: minute hour day month weekday".into());
+        };
+        let mut weekdays = parse_field(weekday, 0, 7, "weekday")?;
+        // Both 0 and 7 mean Sunday.
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days: parse_field(day, 1, 31, "day")?,
+            months: parse_field(month, 1, 12, "month")?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    /// The first matching minute strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date();
+        for _ in 0..CRON_HORIZON_DAYS {
+            if self.matches_date(
+                date.month(),
+                date.day(),
+                date.weekday().num_days_from_sunday(),
+            ) {
+                for hour in (0..24).filter(|hour| bit(self.hours, *hour)) {
+                    for minute in (0..60).filter(|minute| bit(self.minutes, *minute)) {
+                        let candidate: NaiveDateTime = date.and_hms_opt(hour, minute, 0)?;
+                        if candidate < start {
+                            continue;
+                        }
+                        // Skips local times that a DST change jumps over.
+                        if let Some(time) = Local.from_local_datetime(&candidate).earliest() {
+                            return Some(time);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn matches_date(&self, month: u32, day: u32, weekday: u32) -> bool {
+        if !bit(self.months, month) {
+            return false;
+        }
+        let day_matches = bit(self.days, day);
+        let weekday_matches = bit(self.weekdays, weekday);
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday_matches,
+            (false, true) => day_matches,
+            (false, false) => day_matches || weekday_matches,
+        }
+    }
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parses `*`, `5`, `1-5`, `*/15`, `10-50/10` and comma-separated lists of them.
+fn parse_field(text: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid {name} field \"{text}\"");
+    let mut set = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        let (low, high) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((low, high)) => (
+                    low.parse().map_err(|_| invalid())?,
+                    high.parse().map_err(|_| invalid())?,
+                ),
+                None => {
+                    let value = range.parse().map_err(|_| invalid())?;
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+        if step == 0 || low < min || high > max || low > high {
+            return Err(invalid());
+        }
+        for value in (low..=high).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+/// A daily window, in local time, during which no scheduled run starts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuietWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietWindow {
+    /// `start` and `end` are `HH:MM`; a window whose start is after its end spans midnight.
+    pub fn parse(start: &str, end: &str) -> Result<Self, String> {
+        let time = |text: &str| {
+            NaiveTime::parse_from_str(text.trim(), "%H:%M")
+                .map_err(|_| format!("Quiet hours must be HH:MM, not \"{text}\""))
+        };
+        Ok(Self {
+            start: time(start)?,
+            end: time(end)?,
+        })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    pub fn end(&self) -> NaiveTime {
+        self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(month: u32, day: u32, hour: u32, minute: u32, second: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, month, day, hour, minute, second)
+            .unwrap()
+    }
+
+    fn time(text: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(text, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn interval_runs_at_once_then_after_each_interval() {
+        let schedule = Schedule::parse("every 90m").unwrap();
+        assert_eq!(schedule, Schedule::Every(Duration::minutes(90)));
+        let now = at(1, 14, 9, 30, 0);
+        assert_eq!(schedule.next_run(None, now), Some(now));
+        assert_eq!(
+            schedule.next_run(Some(at(1, 14, 9, 0, 0)), now),
+            Some(at(1, 14, 10, 30, 0))
+        );
+        assert_eq!(
+            Schedule::parse("every 2 hours").unwrap(),
+            Schedule::Every(Duration::hours(2))
+        );
+        assert!(Schedule::parse("every 0m").is_err());
+        assert!(Schedule::parse("every 5s").is_err());
+        assert!(Schedule::parse("every").is_err());
+    }
+
+    #[test]
+    fn cron_fires_at_the_first_match_after_its_anchor() {
+        let schedule = Schedule::parse("0 2 * * *").unwrap();
+        let anchor = at(1, 14, 1, 59, 30);
+        assert_eq!(
+            schedule.next_run(Some(anchor), anchor),
+            Some(at(1, 14, 2, 0, 0))
+        );
+        // Due on the first tick after 02:00, and once only after a night asleep.
+        let now = at(1, 14, 2, 0, 20);
+        assert!(schedule.next_run(Some(anchor), now).unwrap() <= now);
+        let woke = at(1, 17, 8, 0, 0);
+        assert_eq!(
+            schedule.next_run(Some(anchor), woke),
+            Some(at(1, 14, 2, 0, 0))
+        );
+        assert_eq!(
+            schedule.next_run(Some(woke), woke),
+            Some(at(1, 18, 2, 0, 0))
+        );
+    }
+
+    #[test]
+    fn cron_without_last_run_waits_for_its_next_match() {
+        let schedule = Schedule::parse("@hourly").unwrap();
+        let now = at(1, 14, 9, 0, 0);
+        assert_eq!(schedule.next_run(None, now), Some(at(1, 14, 10, 0, 0)));
+    }
+
+    #[test]
+    fn parses_cron_fields() {
+        let cron = Cron::parse("*/15 9-17 * 1,7 1-5").unwrap();
+        assert_eq!(cron.minutes, 1 | 1 << 15 | 1 << 30 | 1 << 45);
+        assert_eq!(cron.hours, ((1 << 18) - 1) & !((1 << 9) - 1));
+        assert_eq!(cron.months, 1 << 1 | 1 << 7);
+        assert_eq!(cron.weekdays, 0b111110);
+        assert!(cron.any_day && !cron.any_weekday);
+
+        let cron = Cron::parse("5 0 1 * 7").unwrap();
+        assert_eq!(cron.minutes, 1 << 5);
+        assert!(bit(cron.weekdays, 0), "7 is also Sunday");
+        assert_eq!(
+            Cron::parse("10-50/20 * * * *").unwrap().minutes,
+            1 << 10 | 1 << 30 | 1 << 50
+        );
+        assert_eq!(
+            Cron::parse("30/10 * * * *").unwrap().minutes,
+            1 << 30 | 1 << 40 | 1 << 50
+        );
+    }
+
+    #[test]
+    fn rejects_bad_cron_fields() {
+        for expression in [
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "30-10 * * * *",
+            "a * * * *",
+            "1,,2 * * * *",
+        ] {
+            assert!(Cron::parse(expression).is_err(), "{expression}");
+        }
+        assert!(Schedule::parse("0 0 30 2 *").is_err());
+    }
+
+    #[test]
+    fn cron_matches_either_restricted_day_field() {
+        // The 13th, or any Friday.
+        let cron = Cron::parse("0 12 13 * 5").unwrap();
+        assert_eq!(
+            cron.next_after(at(1, 10, 0, 0, 0)),
+            Some(at(1, 13, 12, 0, 0))
+        );
+        assert_eq!(
+            cron.next_after(at(1, 13, 12, 0, 0)),
+            Some(at(1, 16, 12, 0, 0))
+        );
+    }
+
+    #[test]
+    fn quiet_window_within_a_day() {
+        let window = QuietWindow::parse("09:00", "17:30").unwrap();
+        assert!(!window.contains(time("08:59")));
+        assert!(window.contains(time("09:00")));
+        assert!(window.contains(time("17:29")));
+        assert!(!window.contains(time("17:30")));
+    }
+
+    #[test]
+    fn quiet_window_wraps_around_midnight() {
+        let window = QuietWindow::parse("22:00", " 06:30 ").unwrap();
+        assert!(window.contains(time("22:00")));
+        assert!(window.contains(time("23:59")));
+        assert!(window.contains(time("00:00")));
+        assert!(window.contains(time("06:29")));
+        assert!(!window.contains(time("06:30")));
+        assert!(!window.contains(time("12:00")));
+        assert!(!window.contains(time("21:59")));
+        assert_eq!(window.end(), time("06:30"));
+        assert!(QuietWindow::parse("25:00", "06:00").is_err());
+        assert!(QuietWindow::parse("10pm", "06:00").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! Runs scheduled ingests and keeps the tray's "Next run" line current.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use context_cache_desktop_lib::dto::IngestRequest;
+use context_cache_desktop_lib::schedule::Schedule;
+use context_cache_desktop_lib::settings::{self, ScheduleRule, ScheduleSettings};
+use tauri::{AppHandle, Manager};
+
+use crate::health::{BackendState, HealthMonitor};
+use crate::{app_settings, conditions, ingest, pause, tray};
+
+const TICK: Duration = Duration::from_secs(30);
+const FILE_NAME: &str = "schedule.json";
+
+/// When each rule last started, keyed by [`ScheduleRule::key`] and persisted across restarts.
+///
+/// A cron rule that never ran holds the time it was first seen instead, so its next match
+/// after that time comes due.
+pub struct Scheduler {
+    path: PathBuf,
+    last_runs: Mutex<BTreeMap<String, DateTime<Local>>>,
+}
+
+impl Scheduler {
+    fn load() -> Self {
+        let path = settings::default_path().with_file_name(FILE_NAME);
+        let stored: BTreeMap<String, String> = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+                eprintln!("Failed to parse {}: {err}; starting afresh", path.display());
+                BTreeMap::new()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => {
+                eprintln!("Failed to read {}: {err}; starting afresh", path.display());
+                BTreeMap::new()
+            }
+        };
+        let last_runs = stored
+            .into_iter()
+            .filter_map(|(key, time)| {
+                let time = DateTime::parse_from_rfc3339(&time).ok()?;
+                Some((key, time.with_timezone(&Local)))
+            })
+            .collect();
+        Self {
+            path,
+            last_runs: Mutex::new(last_runs),
+        }
+    }
+
+    fn last_run(&self, rule: &ScheduleRule) -> Option<DateTime<Local>> {
+        self.last_runs.lock().unwrap().get(&rule.key()).copied()
+    }
+
+    /// Records a start and forgets rules that no longer exist.
+    fn record(&self, rule: &ScheduleRule, time: DateTime<Local>, rules: &[ScheduleRule]) {
+        let mut last_runs = self.last_runs.lock().unwrap();
+        last_runs.insert(rule.key(), time);
+        last_runs.retain(|key, _| rules.iter().any(|rule| &rule.key() == key));
+        let stored: BTreeMap<&String, String> = last_runs
+            .iter()
+            .map(|(key, time)| (key, time.to_rfc3339()))
+            .collect();
+        let write = || -> io::Result<()> {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_vec_pretty(&stored)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let tmp = self.path.with_extension("json.tmp");
+            fs::write(&tmp, json)?;
+            fs::rename(&tmp, &self.path)
+        };
+        if let Err(err) = write() {
+            eprintln!("Failed to write {}: {err}", self.path.display());
+        }
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    app.manage(Scheduler::load());
+    let app = app.clone();
+    thread::spawn(move || loop {
+        tick(&app);
+        thread::sleep(TICK);
+    });
+}
+
+/// Starts every due rule unless something holds runs back, then updates the tooltip.
+fn tick(app: &AppHandle) {
+    let settings = app_settings::current(app);
+    let rules = settings.schedule_rules();
+    let scheduler = app.state::<Scheduler>();
+    let now = Local::now();
+    let held_back = hold_reason(app, &settings.schedule, now);
+    let mut waiting = held_back.clone();
+    let mut upcoming: Option<(DateTime<Local>, &ScheduleRule)> = None;
+    for rule in &rules {
+        // Settings are validated on save; a hand-edited file may still hold a bad rule.
+        let schedule = match Schedule::parse(&rule.schedule) {
+            Ok(schedule) => schedule,
+            Err(err) => {
+                eprintln!("Skipping schedule: {err}");
+                continue;
+            }
+        };
+        let last_run = match scheduler.last_run(rule) {
+            None if matches!(schedule, Schedule::Cron(_)) => {
+                scheduler.record(rule, now, &rules);
+                Some(now)
+            }
+            last_run => last_run,
+        };
+        let Some(mut due) = schedule.next_run(last_run, now) else {
+            continue;
+        };
+        if due <= now && held_back.is_none() {
+            let request = request(rule);
+            match ingest::claim(app, &request) {
+                Ok(in_flight) => {
+                    scheduler.record(rule, now, &rules);
+                    ingest::spawn_claimed(app, request, in_flight);
+                    let Some(next) = schedule.next_run(Some(now), now) else {
+                        continue;
+                    };
+                    due = next;
+                }
+                // Not recorded, so the rule stays due and the next tick tries again.
+                Err(err) => {
+                    eprintln!("Postponing scheduled ingest: {err}");
+                    waiting.get_or_insert_with(|| "after the running ingest".into());
+                }
+            }
+        }
+        if upcoming.is_none_or(|(earliest, _)| due < earliest) {
+            upcoming = Some((due, rule));
+        }
+    }
+    let line = upcoming.map(|(due, rule)| {
+        let target = match &rule.source_id {
+            Some(id) => tray::source_label(app, id).unwrap_or_else(|| id.clone()),
+            None => "all sources".to_string(),
+        };
+        match &waiting {
+            Some(reason) if due <= now => format!("Next run: {target}, {reason}"),
+            _ if due.date_naive() == now.date_naive() => {
+                format!("Next run: {target} at {}", due.format("%H:%M"))
+            }
+            _ => format!("Next run: {target} on {}", due.format("%a %H:%M")),
+        }
+    });
+    if let Err(err) = tray::show_next_run(app, line) {
+        eprintln!("Failed to update tray tooltip: {err}");
+    }
+}
+
+/// Why due runs have to wait, if they do; unknown power or idle state never holds them back.
+fn hold_reason(
+    app: &AppHandle,
+    schedule: &ScheduleSettings,
+    now: DateTime<Local>,
+) -> Option<String> {
+    if pause::is_paused(app) {
+        return Some("indexing is paused".into());
+    }
+    let connected = app
+        .try_state::<HealthMonitor>()
+        .is_some_and(|monitor| monitor.current().state == BackendState::Connected);
+    if !connected {
+        return Some("waiting for the backend".into());
+    }
+    if let Some(window) = schedule
+        .quiet_hours
+        .as_ref()
+        .and_then(|quiet_hours| quiet_hours.window().ok())
+    {
+        if window.contains(now.time()) {
+            return Some(format!(
+                "after quiet hours end at {}",
+                window.end().format("%H:%M")
+            ));
+        }
+    }
+    if schedule.require_ac_power && conditions::on_ac_power() == Some(false) {
+        return Some("waiting for AC power".into());
+    }
+    let idle_needed = Duration::from_secs(u64::from(schedule.require_idle_minutes) * 60);
+    if schedule.require_idle_minutes > 0
+        && conditions::idle_time().is_some_and(|idle| idle < idle_needed)
+    {
+        return Some("waiting until the computer is idle".into());
+    }
+    None
+}
+
+fn request(rule: &ScheduleRule) -> IngestRequest {
+    match &rule.source_id {
+        Some(id) => IngestRequest {
+            sources: Some(vec![id.clone()]),
+            ..IngestRequest::default()
+        },
+        None => IngestRequest::all(),
+    }
+}
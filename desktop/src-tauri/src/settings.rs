@@ -0,0 +1,638 @@
+//! Desktop settings persisted as JSON in the app config directory.
+//!
+//! Environment variables override individual values at runtime but are never written back.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::DEFAULT_HOST;
+use crate::dto::{QueryFilters, QueryRequest, DEFAULT_K};
+use crate::schedule::{QuietWindow, Schedule};
+use crate::tls;
+
+/// Matches `identifier` in `tauri.conf.json`, so the file lives in Tauri's app config dir.
+pub const APP_IDENTIFIER: &str = "com.contextcache.desktop";
+const FILE_NAME: &str = "settings.json";
+
+pub const DEFAULT_SIDECAR_COMMAND: &str =
+    "python -m uvicorn context_cache.app:app --host 127.0.0.1 --port {port}";
+pub const DEFAULT_SIDECAR_PORT: u16 = 5173;
+pub const DEFAULT_PROFILE: &str = "Local";
+pub const DEFAULT_QUICK_SEARCH_SHORTCUT: &str = "CmdOrCtrl+Shift+Space";
+pub const DEFAULT_CLIPPING_SHORTCUT: &str = "CmdOrCtrl+Alt+V";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Backends the shell can talk to. The first one is the local backend a sidecar serves.
+    pub profiles: Vec<BackendProfile>,
+    pub active_profile: String,
+    /// Minutes between automatic "ingest all" runs; `0` disables them. Kept alongside
+    /// `schedule.rules`, which it joins as an `every <n>m` rule for all sources.
+    pub auto_ingest_minutes: u32,
+    pub schedule: ScheduleSettings,
+    pub notifications: NotificationSettings,
+    pub query: QueryDefaults,
+    pub quick_search: QuickSearchSettings,
+    pub clippings: ClippingSettings,
+    pub sidecar: SidecarSettings,
+    pub network: NetworkSettings,
+    pub watcher: WatcherSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            profiles: vec![BackendProfile::default()],
+            active_profile: DEFAULT_PROFILE.to_string(),
+            auto_ingest_minutes: 0,
+            schedule: ScheduleSettings::default(),
+            notifications: NotificationSettings::default(),
+            query: QueryDefaults::default(),
+            quick_search: QuickSearchSettings::default(),
+            clippings: ClippingSettings::default(),
+            sidecar: SidecarSettings::default(),
+            network: NetworkSettings::default(),
+            watcher: WatcherSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendProfile {
+    pub name: String,
+    pub base_url: String,
+    pub auth_token: Option<String>,
+    /// Applied to queries that do not pass filters of their own.
+    pub filters: QueryFilters,
+    pub tls: TlsSettings,
+}
+
+impl Default for BackendProfile {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            base_url: DEFAULT_HOST.to_string(),
+            auth_token: None,
+            filters: QueryFilters::default(),
+            tls: TlsSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsSettings {
+    /// PEM file of extra CA certificates trusted on top of the public roots.
+    pub ca_bundle: Option<String>,
+    /// SHA-256 fingerprints of accepted server certificates. When set, only a matching
+    /// certificate is accepted, whoever signed it; this is how self-signed servers are trusted.
+    pub pinned_certs: Vec<String>,
+}
+
+impl TlsSettings {
+    pub fn is_default(&self) -> bool {
+        self.ca_bundle
+            .as_deref()
+            .is_none_or(|path| path.trim().is_empty())
+            && self.pinned_certs.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub ingest_finished: bool,
+    pub ingest_failed: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            ingest_finished: true,
+            ingest_failed: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryDefaults {
+    pub k: u32,
+    pub rerank: Option<bool>,
+    pub hybrid: Option<bool>,
+}
+
+impl Default for QueryDefaults {
+    fn default() -> Self {
+        Self {
+            k: DEFAULT_K,
+            rerank: None,
+            hybrid: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuickSearchSettings {
+    /// Global shortcut that toggles the quick-search popup; empty disables it.
+    pub shortcut: String,
+    /// What Enter does with the selected result; Ctrl/Cmd+Enter does the other.
+    pub enter_action: ProvenanceAction,
+}
+
+impl Default for QuickSearchSettings {
+    fn default() -> Self {
+        Self {
+            shortcut: DEFAULT_QUICK_SEARCH_SHORTCUT.to_string(),
+            enter_action: ProvenanceAction::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClippingSettings {
+    /// Global shortcut that saves the clipboard as a note; empty disables it.
+    pub shortcut: String,
+    /// Folder the notes are written to; see [`ClippingSettings::folder`] for the default.
+    pub folder: Option<String>,
+}
+
+impl Default for ClippingSettings {
+    fn default() -> Self {
+        Self {
+            shortcut: DEFAULT_CLIPPING_SHORTCUT.to_string(),
+            folder: None,
+        }
+    }
+}
+
+impl ClippingSettings {
+    /// The configured folder, or `Context Cache/Clippings` in the user's documents folder.
+    pub fn folder(&self) -> PathBuf {
+        match self.folder.as_deref().map(str::trim) {
+            Some(folder) if !folder.is_empty() => PathBuf::from(folder),
+            _ => dirs::document_dir()
+                .or_else(dirs::home_dir)
+                .unwrap_or_else(env::temp_dir)
+                .join("Context Cache")
+                .join("Clippings"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvenanceAction {
+    #[default]
+    Open,
+    Copy,
+}
+
+impl ProvenanceAction {
+    pub fn other(self) -> Self {
+        match self {
+            ProvenanceAction::Open => ProvenanceAction::Copy,
+            ProvenanceAction::Copy => ProvenanceAction::Open,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SidecarSettings {
+    pub enabled: bool,
+    /// Command line used to launch the backend; `{port}` is substituted. Parts containing
+    /// spaces are quoted: `"C:\Program Files\Python\python.exe" -m uvicorn …`.
+    pub command: String,
+    pub port: u16,
+    pub db_path: Option<String>,
+}
+
+impl Default for SidecarSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: DEFAULT_SIDECAR_COMMAND.to_string(),
+            port: DEFAULT_SIDECAR_PORT,
+            db_path: None,
+        }
+    }
+}
+
+impl SidecarSettings {
+    pub fn base_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    /// Program and arguments of [`Self::command`], with `{port}` substituted.
+    pub fn argv(&self) -> Result<Vec<String>, String> {
+        let argv = split_command_line(&self.command.replace("{port}", &self.port.to_string()))?;
+        if argv.is_empty() {
+            return Err("Sidecar command is empty".into());
+        }
+        Ok(argv)
+    }
+}
+
+/// Splits on whitespace outside single or double quotes. Backslashes are kept as they are, so
+/// Windows paths need no escaping.
+fn split_command_line(line: &str) -> Result<Vec<String>, String> {
+    let mut parts = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for ch in line.chars() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => current.get_or_insert_with(String::new).push(ch),
+            None if ch == '"' || ch == '\'' => {
+                quote = Some(ch);
+                current.get_or_insert_with(String::new);
+            }
+            None if ch.is_whitespace() => parts.extend(current.take()),
+            None => current.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("Unterminated quote in sidecar command: {line}"));
+    }
+    parts.extend(current);
+    Ok(parts)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleSettings {
+    pub rules: Vec<ScheduleRule>,
+    /// No scheduled run starts inside this window; due runs wait for its end.
+    pub quiet_hours: Option<QuietHours>,
+    /// Only start scheduled runs on mains power. Ignored where the platform cannot tell.
+    pub require_ac_power: bool,
+    /// Only start scheduled runs after this many minutes without input; `0` disables the check.
+    /// Ignored where the platform cannot tell.
+    pub require_idle_minutes: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleRule {
+    /// Source to ingest; `None` ingests all of them.
+    pub source_id: Option<String>,
+    /// See [`Schedule::parse`], e.g. `every 10m` or `0 2 * * *`.
+    pub schedule: String,
+}
+
+impl ScheduleRule {
+    /// Identifies the rule in the persisted schedule state; editing it starts afresh.
+    pub fn key(&self) -> String {
+        format!(
+            "{}|{}",
+            self.source_id.as_deref().unwrap_or("*"),
+            self.schedule.trim()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// Local time as `HH:MM`.
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    pub fn window(&self) -> Result<QuietWindow, String> {
+        QuietWindow::parse(&self.start, &self.end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatcherSettings {
+    /// Watch registered folders from the shell and ingest changed files by path.
+    pub enabled: bool,
+    /// Quiet period that ends a burst of changes; the burst is then ingested as one batch.
+    pub debounce_ms: u64,
+}
+
+impl Default for WatcherSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            debounce_ms: 1000,
+        }
+    }
+}
+
+/// Timeouts are in seconds; `/health` and `/ingest` get their own since one must answer at once
+/// and the other may embed a whole folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    pub connect_timeout_secs: u64,
+    pub health_timeout_secs: u64,
+    pub request_timeout_secs: u64,
+    pub ingest_timeout_secs: u64,
+    /// Extra attempts for failed GET requests, with jittered exponential backoff.
+    pub get_retries: u32,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 5,
+            health_timeout_secs: 3,
+            request_timeout_secs: 30,
+            ingest_timeout_secs: 1800,
+            get_retries: 2,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads settings from `path`, falling back to defaults when the file does not exist.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut settings: Self = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(err) => return Err(err),
+        };
+        settings.normalize();
+        Ok(settings)
+    }
+
+    /// Guarantees at least one profile and an `active_profile` that names one of them.
+    pub fn normalize(&mut self) {
+        if self.profiles.is_empty() {
+            self.profiles.push(BackendProfile::default());
+        }
+        if self.profile(&self.active_profile).is_none() {
+            self.active_profile = self.profiles[0].name.clone();
+        }
+    }
+
+    /// Rejects profiles the tray could not tell apart or the client could not reach.
+    pub fn validate(&self) -> Result<(), String> {
+        for (index, profile) in self.profiles.iter().enumerate() {
+            let name = profile.name.trim();
+            if name.is_empty() {
+                return Err("Profile names must not be empty".into());
+            }
+            if self.profiles[..index]
+                .iter()
+                .any(|other| other.name == profile.name)
+            {
+                return Err(format!("Duplicate profile name \"{name}\""));
+            }
+            let url = url::Url::parse(&profile.base_url)
+                .map_err(|err| format!("Invalid URL for profile \"{name}\": {err}"))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!("Profile \"{name}\" must use http or https"));
+            }
+            if profile.auth_token.is_some() && !tls::may_send_token(&profile.base_url) {
+                return Err(format!(
+                    "Profile \"{name}\" has a token but uses plain HTTP to another machine; use https://"
+                ));
+            }
+            tls::validate(&profile.tls).map_err(|err| format!("Profile \"{name}\": {err}"))?;
+        }
+        self.sidecar.argv()?;
+        let network = &self.network;
+        if [
+            network.connect_timeout_secs,
+            network.health_timeout_secs,
+            network.request_timeout_secs,
+            network.ingest_timeout_secs,
+        ]
+        .contains(&0)
+        {
+            return Err("Timeouts must be at least one second".into());
+        }
+        for rule in &self.schedule.rules {
+            Schedule::parse(&rule.schedule)?;
+        }
+        if let Some(quiet_hours) = &self.schedule.quiet_hours {
+            quiet_hours.window()?;
+        }
+        if self.watcher.debounce_ms < 100 {
+            return Err("The watcher delay must be at least 100 ms".into());
+        }
+        Ok(())
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&BackendProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// The profile every request is routed through; falls back to the first one.
+    ///
+    /// Panics without profiles, which `load` and `normalize` rule out.
+    pub fn active(&self) -> &BackendProfile {
+        self.profile(&self.active_profile)
+            .unwrap_or(&self.profiles[0])
+    }
+
+    pub fn active_mut(&mut self) -> &mut BackendProfile {
+        let index = self
+            .profiles
+            .iter()
+            .position(|profile| profile.name == self.active_profile)
+            .unwrap_or(0);
+        &mut self.profiles[index]
+    }
+
+    /// True when the active profile is the local one a sidecar would serve.
+    pub fn active_is_local(&self) -> bool {
+        self.profiles
+            .first()
+            .is_some_and(|profile| profile.name == self.active().name)
+    }
+
+    /// Scheduled ingests, including the legacy `auto_ingest_minutes` interval.
+    pub fn schedule_rules(&self) -> Vec<ScheduleRule> {
+        let mut rules = self.schedule.rules.clone();
+        if self.auto_ingest_minutes > 0 {
+            rules.push(ScheduleRule {
+                source_id: None,
+                schedule: format!("every {}m", self.auto_ingest_minutes),
+            });
+        }
+        rules
+    }
+
+    /// Gives the local profile `token` unless one is configured or overridden already.
+    pub fn apply_local_token(&mut self, token: &str) {
+        if let Some(local) = self.profiles.first_mut() {
+            local.auth_token.get_or_insert_with(|| token.to_string());
+        }
+    }
+
+    /// Token the local backend expects; a sidecar is started with it.
+    pub fn local_token(&self) -> Option<&str> {
+        self.profiles.first()?.auth_token.as_deref()
+    }
+
+    /// Writes settings atomically so a crash never leaves a truncated file behind.
+    ///
+    /// Profile tokens belong in the OS keyring (see [`crate::api_token`]) and are left out, except
+    /// for the profiles in `plaintext_tokens`, whose token the keyring could not take.
+    pub fn save(&self, path: &Path, plaintext_tokens: &BTreeSet<String>) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut stored = self.clone();
+        for profile in &mut stored.profiles {
+            if !plaintext_tokens.contains(&profile.name) {
+                profile.auth_token = None;
+            }
+        }
+        let json = serde_json::to_vec_pretty(&stored)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, path)
+    }
+
+    /// Applies `CTXC_*` environment overrides and returns the names of the fields they replaced.
+    ///
+    /// `CTXC_HOST` and `CTXC_API_TOKEN` apply to the active profile.
+    pub fn apply_env_overrides(&mut self) -> Vec<&'static str> {
+        let mut overridden = Vec::new();
+        if let Some(host) = env_value("CTXC_HOST") {
+            self.active_mut().base_url = host;
+            overridden.push("base_url");
+        }
+        if let Some(token) = env_value("CTXC_API_TOKEN") {
+            self.active_mut().auth_token = Some(token);
+            overridden.push("auth_token");
+        }
+        if let Some(enabled) = env_value("CTXC_SIDECAR") {
+            self.sidecar.enabled = matches!(enabled.as_str(), "1" | "true" | "yes" | "on");
+            overridden.push("sidecar.enabled");
+        }
+        if let Some(command) = env_value("CTXC_SIDECAR_CMD") {
+            self.sidecar.command = command;
+            overridden.push("sidecar.command");
+        }
+        if let Some(port) = env_value("CTXC_SIDECAR_PORT").and_then(|port| port.parse().ok()) {
+            self.sidecar.port = port;
+            overridden.push("sidecar.port");
+        }
+        if let Some(db_path) = env_value("CTXC_SIDECAR_DB_PATH") {
+            self.sidecar.db_path = Some(db_path);
+            overridden.push("sidecar.db_path");
+        }
+        overridden
+    }
+
+    /// URL the shell should talk to: the sidecar when it serves the active profile.
+    pub fn backend_url(&self) -> String {
+        if self.sidecar.enabled && self.active_is_local() {
+            self.sidecar.base_url()
+        } else {
+            self.active().base_url.clone()
+        }
+    }
+
+    /// Fills in whatever the caller left unset from the query defaults and the active profile.
+    pub fn query_request(
+        &self,
+        query: String,
+        k: Option<u32>,
+        rerank: Option<bool>,
+        hybrid: Option<bool>,
+        filters: Option<QueryFilters>,
+    ) -> QueryRequest {
+        let profile_filters = Some(self.active().filters.clone())
+            .filter(|filters| *filters != QueryFilters::default());
+        QueryRequest {
+            query,
+            k: k.unwrap_or(self.query.k),
+            rerank: rerank.or(self.query.rerank),
+            hybrid: hybrid.or(self.query.hybrid),
+            filters: filters.or(profile_filters),
+        }
+    }
+}
+
+/// `settings.json` inside the per-user config directory (`~/.config/com.contextcache.desktop` on Linux).
+pub fn default_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_IDENTIFIER)
+        .join(FILE_NAME)
+}
+
+fn env_value(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_sidecar_commands() {
+        let sidecar = SidecarSettings {
+            command: r#""C:\Program Files\Python\python.exe" -m uvicorn --port {port}"#.into(),
+            port: 8080,
+            ..SidecarSettings::default()
+        };
+        assert_eq!(
+            sidecar.argv().unwrap(),
+            [
+                r"C:\Program Files\Python\python.exe",
+                "-m",
+                "uvicorn",
+                "--port",
+                "8080"
+            ]
+        );
+        assert_eq!(
+            split_command_line("'/Applications/My App/run.sh'  ''").unwrap(),
+            ["/Applications/My App/run.sh", ""]
+        );
+        assert!(split_command_line("\"unterminated").is_err());
+        assert!(SidecarSettings {
+            command: "   ".into(),
+            ..SidecarSettings::default()
+        }
+        .argv()
+        .is_err());
+    }
+
+    #[test]
+    fn saves_only_the_tokens_the_keyring_refused() {
+        let dir = env::temp_dir().join(format!("ctxc-settings-{}", std::process::id()));
+        let path = dir.join("settings.json");
+        let mut settings = Settings::default();
+        settings.profiles[0].auth_token = Some("local-secret".into());
+        settings.profiles.push(BackendProfile {
+            name: "work".into(),
+            base_url: "https://ctx.example.com".into(),
+            auth_token: Some("work-secret".into()),
+            ..BackendProfile::default()
+        });
+        let plaintext = BTreeSet::from(["work".to_string()]);
+        settings.save(&path, &plaintext).unwrap();
+        let saved = Settings::load(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(saved.profiles[0].auth_token, None);
+        assert_eq!(
+            saved.profile("work").unwrap().auth_token.as_deref(),
+            Some("work-secret")
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Mutex;
+
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// A global shortcut bound from the settings; rebinding replaces the previous one.
+#[derive(Default)]
+pub struct BoundShortcut(Mutex<Option<Shortcut>>);
+
+/// Parses a shortcut such as `CmdOrCtrl+Shift+Space`; an empty string means "no shortcut".
+pub fn parse(text: &str, name: &str) -> Result<Option<Shortcut>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse()
+        .map(Some)
+        .map_err(|err| format!("Invalid {name} shortcut \"{text}\": {err}"))
+}
+
+impl BoundShortcut {
+    /// Binds `text` to `action`, unless it is already the bound shortcut.
+    pub fn rebind(
+        &self,
+        app: &AppHandle,
+        text: &str,
+        name: &str,
+        action: fn(&AppHandle),
+    ) -> Result<(), String> {
+        let wanted = parse(text, name)?;
+        let mut current = self.0.lock().unwrap();
+        if *current == wanted {
+            return Ok(());
+        }
+        let shortcuts = app.global_shortcut();
+        if let Some(previous) = current.take() {
+            if let Err(err) = shortcuts.unregister(previous) {
+                eprintln!("Failed to unregister {name} shortcut: {err}");
+            }
+        }
+        if let Some(shortcut) = wanted {
+            shortcuts
+                .on_shortcut(shortcut, move |app, _, event| {
+                    if event.state == ShortcutState::Pressed {
+                        action(app);
+                    }
+                })
+                .map_err(|err| format!("Failed to register {name} shortcut \"{text}\": {err}"))?;
+            *current = Some(shortcut);
+        }
+        Ok(())
+    }
+}
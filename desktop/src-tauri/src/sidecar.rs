@@ -0,0 +1,206 @@
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use context_cache_desktop_lib::client::BackendClient;
+use context_cache_desktop_lib::settings::SidecarSettings;
+
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(90);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A backend that stayed up this long is considered healthy again and resets the backoff.
+const STABLE_UPTIME: Duration = Duration::from_secs(120);
+#[cfg(unix)]
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// Builds the configured command line, passing the database path and token.
+fn build_command(config: &SidecarSettings, api_token: Option<&str>) -> Result<Command, String> {
+    let argv = config.argv()?;
+    let mut command = Command::new(&argv[0]);
+    command.args(&argv[1..]);
+    if let Some(db_path) = &config.db_path {
+        command.env("CTXC_DB_PATH", db_path);
+    }
+    if let Some(token) = api_token {
+        command.env("CTXC_API_TOKEN", token);
+    }
+    Ok(command)
+}
+
+/// Supervises a backend process launched by the desktop shell.
+pub struct Sidecar {
+    base_url: String,
+    child: Arc<Mutex<Option<Child>>>,
+    stopping: Arc<AtomicBool>,
+    starting: Arc<AtomicBool>,
+}
+
+impl Sidecar {
+    pub fn spawn(config: SidecarSettings, api_token: Option<String>) -> Self {
+        let client = BackendClient::new(config.base_url()).with_auth_token(api_token);
+        let sidecar = Self {
+            base_url: config.base_url(),
+            child: Arc::new(Mutex::new(None)),
+            stopping: Arc::new(AtomicBool::new(false)),
+            starting: Arc::new(AtomicBool::new(true)),
+        };
+        let child = sidecar.child.clone();
+        let stopping = sidecar.stopping.clone();
+        let starting = sidecar.starting.clone();
+        thread::spawn(move || supervise(config, client, child, stopping, starting));
+        sidecar
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// True while the backend is being launched or restarted and has not reported healthy yet.
+    pub fn is_starting(&self) -> bool {
+        self.starting.load(Ordering::SeqCst)
+    }
+
+    pub fn shutdown(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.lock().unwrap().take() {
+            terminate(child);
+        }
+    }
+}
+
+fn supervise(
+    config: SidecarSettings,
+    client: BackendClient,
+    slot: Arc<Mutex<Option<Child>>>,
+    stopping: Arc<AtomicBool>,
+    starting: Arc<AtomicBool>,
+) {
+    if is_healthy(&client) {
+        eprintln!(
+            "Backend already running at {}; not starting sidecar",
+            client.base_url()
+        );
+        starting.store(false, Ordering::SeqCst);
+        return;
+    }
+
+    let mut backoff = INITIAL_BACKOFF;
+    while !stopping.load(Ordering::SeqCst) {
+        starting.store(true, Ordering::SeqCst);
+        let started = Instant::now();
+        match build_command(&config, client.auth_token()).and_then(|mut command| {
+            command
+                .spawn()
+                .map_err(|err| format!("Failed to start backend `{}`: {err}", config.command))
+        }) {
+            Ok(child) => {
+                let mut guard = slot.lock().unwrap();
+                if stopping.load(Ordering::SeqCst) {
+                    terminate(child);
+                    return;
+                }
+                eprintln!("Started backend sidecar (pid {})", child.id());
+                *guard = Some(child);
+            }
+            Err(err) => {
+                eprintln!("{err}; retrying in {backoff:?}");
+                sleep_unless_stopping(backoff, &stopping);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                continue;
+            }
+        }
+
+        if wait_for_health(&client, &slot, &stopping) {
+            eprintln!("Backend sidecar is healthy at {}", client.base_url());
+        }
+        starting.store(false, Ordering::SeqCst);
+
+        let status = loop {
+            if stopping.load(Ordering::SeqCst) {
+                return;
+            }
+            let mut guard = slot.lock().unwrap();
+            let Some(child) = guard.as_mut() else {
+                return;
+            };
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    guard.take();
+                    break status.to_string();
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    guard.take();
+                    break err.to_string();
+                }
+            }
+            drop(guard);
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        if started.elapsed() >= STABLE_UPTIME {
+            backoff = INITIAL_BACKOFF;
+        }
+        starting.store(true, Ordering::SeqCst);
+        eprintln!("Backend sidecar exited ({status}); restarting in {backoff:?}");
+        sleep_unless_stopping(backoff, &stopping);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+fn wait_for_health(
+    client: &BackendClient,
+    slot: &Mutex<Option<Child>>,
+    stopping: &AtomicBool,
+) -> bool {
+    let deadline = Instant::now() + HEALTH_TIMEOUT;
+    while Instant::now() < deadline && !stopping.load(Ordering::SeqCst) {
+        if is_healthy(client) {
+            return true;
+        }
+        let exited = match slot.lock().unwrap().as_mut() {
+            Some(child) => !matches!(child.try_wait(), Ok(None)),
+            None => true,
+        };
+        if exited {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    eprintln!("Backend sidecar did not report healthy within {HEALTH_TIMEOUT:?}");
+    false
+}
+
+fn is_healthy(client: &BackendClient) -> bool {
+    client.health().map(|health| health.ok).unwrap_or(false)
+}
+
+fn sleep_unless_stopping(duration: Duration, stopping: &AtomicBool) {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline && !stopping.load(Ordering::SeqCst) {
+        thread::sleep(POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now())));
+    }
+}
+
+/// Asks the backend to exit (SIGTERM on Unix) and kills it if it does not within the grace period.
+fn terminate(mut child: Child) {
+    #[cfg(unix)]
+    {
+        // SAFETY: `kill` has no memory-safety preconditions; the pid belongs to our child.
+        unsafe {
+            libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+        }
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
@@ -0,0 +1,175 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use context_cache_desktop_lib::client::{self, BackendClient, Method};
+use context_cache_desktop_lib::dto::{
+    DeleteResponse, SourceCreateRequest, SourceKind, SourceResponse, SourceUpdateRequest,
+};
+use tauri::AppHandle;
+use url::Url;
+
+use crate::{outbox, tray, watcher};
+
+#[tauri::command]
+pub async fn list_sources(app_handle: AppHandle) -> Result<Vec<SourceResponse>, String> {
+    crate::blocking(move || {
+        let sources = crate::client(&app_handle).list_sources()?;
+        show_sources(&app_handle, &sources);
+        Ok(sources)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn add_source(
+    app_handle: AppHandle,
+    source: SourceCreateRequest,
+) -> Result<SourceResponse, String> {
+    crate::blocking(move || {
+        let created = register_source(&app_handle, &crate::client(&app_handle), source)?;
+        sources_changed(&app_handle);
+        Ok(created)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_source(
+    app_handle: AppHandle,
+    source_id: String,
+    changes: SourceUpdateRequest,
+) -> Result<SourceResponse, String> {
+    crate::blocking(move || {
+        let client = crate::client(&app_handle);
+        let changes = serde_json::to_value(&changes).map_err(|e| e.to_string())?;
+        let path = client::source_path(&source_id);
+        let updated =
+            outbox::send(&app_handle, &client, Method::Patch, &path, Some(changes))?.done()?;
+        sources_changed(&app_handle);
+        Ok(updated)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_source(
+    app_handle: AppHandle,
+    source_id: String,
+) -> Result<DeleteResponse, String> {
+    crate::blocking(move || {
+        let client = crate::client(&app_handle);
+        let path = client::source_path(&source_id);
+        let deleted = outbox::send(&app_handle, &client, Method::Delete, &path, None)?.done()?;
+        sources_changed(&app_handle);
+        Ok(deleted)
+    })
+    .await
+}
+
+/// Brings shell-side views of the source list (the tray submenu, the watcher) up to date.
+///
+/// Performs blocking I/O; call it from a worker thread.
+pub fn sources_changed(app_handle: &AppHandle) {
+    match crate::client(app_handle).list_sources() {
+        Ok(sources) => show_sources(app_handle, &sources),
+        Err(err) => eprintln!("Failed to refresh sources: {err}"),
+    }
+}
+
+fn show_sources(app_handle: &AppHandle, sources: &[SourceResponse]) {
+    if let Err(err) = tray::show_sources(app_handle, sources) {
+        eprintln!("Failed to update tray sources: {err}");
+    }
+    watcher::sync(app_handle, sources);
+}
+
+/// Validates the source path locally and registers it with the backend, queueing it while offline.
+pub fn register_source(
+    app: &AppHandle,
+    client: &BackendClient,
+    source: SourceCreateRequest,
+) -> Result<SourceResponse, String> {
+    let source = serde_json::to_value(prepare_source(source)?).map_err(|e| e.to_string())?;
+    outbox::send(app, client, Method::Post, "/sources", Some(source))?.done()
+}
+
+/// Checks the source path and replaces it with its canonical form.
+fn prepare_source(mut source: SourceCreateRequest) -> Result<SourceCreateRequest, String> {
+    let path = validate_source_path(source.kind, &source.uri)?;
+    source.uri = path.to_string_lossy().into_owned();
+    Ok(source)
+}
+
+/// Checks that `uri` exists, is readable and matches `kind`; returns the canonical path.
+pub fn validate_source_path(kind: SourceKind, uri: &str) -> Result<PathBuf, String> {
+    let path = expand_path(uri)?;
+    let metadata =
+        fs::metadata(&path).map_err(|err| format!("Cannot access {}: {err}", path.display()))?;
+    match kind {
+        SourceKind::File => {
+            if !metadata.is_file() {
+                return Err(format!("{} is not a file", path.display()));
+            }
+            fs::File::open(&path)
+                .map_err(|err| format!("{} is not readable: {err}", path.display()))?;
+        }
+        _ => {
+            if !metadata.is_dir() {
+                return Err(format!("{} is not a directory", path.display()));
+            }
+            fs::read_dir(&path)
+                .map_err(|err| format!("{} is not readable: {err}", path.display()))?;
+        }
+    }
+    path.canonicalize()
+        .map_err(|err| format!("Cannot resolve {}: {err}", path.display()))
+}
+
+/// Turns a `file://` URL or a path into a path, expanding a leading `~` or `~/` to the home folder.
+///
+/// `~user` forms are rejected rather than guessed at.
+pub fn expand_path(uri: &str) -> Result<PathBuf, String> {
+    let raw = uri.trim();
+    if let Some(path) = Url::parse(raw)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok())
+    {
+        return Ok(path);
+    }
+    let Some(rest) = raw.strip_prefix('~') else {
+        return Ok(PathBuf::from(raw));
+    };
+    if !(rest.is_empty() || rest.starts_with(['/', '\\'])) {
+        return Err(format!(
+            "{raw}: only ~ and ~/ are expanded; spell out another user's home folder"
+        ));
+    }
+    match env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")) {
+        Some(home) => Ok(Path::new(&home).join(rest.trim_start_matches(['/', '\\']))),
+        None => Ok(PathBuf::from(raw)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_only_the_current_users_home() {
+        let home = PathBuf::from(
+            env::var_os("HOME")
+                .or_else(|| env::var_os("USERPROFILE"))
+                .unwrap(),
+        );
+        assert_eq!(expand_path("~").unwrap(), home);
+        assert_eq!(expand_path(" ~/notes ").unwrap(), home.join("notes"));
+        assert_eq!(
+            expand_path("/tmp/notes").unwrap(),
+            PathBuf::from("/tmp/notes")
+        );
+        assert!(expand_path("~alice/notes").is_err());
+        assert!(expand_path("~alice").is_err());
+    }
+}
@@ -0,0 +1,299 @@
+//! HTTPS for remote backends: extra CA bundles, certificate pinning and readable certificate errors.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Arc;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{self, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use sha2::{Digest, Sha256};
+use url::{Host, Url};
+
+use crate::settings::TlsSettings;
+
+/// Builds an agent trusting the public roots plus the profile's CA bundle, or only its pins when set.
+pub fn agent(builder: ureq::AgentBuilder, settings: &TlsSettings) -> Result<ureq::Agent, String> {
+    if settings.is_default() {
+        return Ok(builder.build());
+    }
+    Ok(builder
+        .tls_config(Arc::new(client_config(settings)?))
+        .build())
+}
+
+/// Checks that the CA bundle loads and every pin is a SHA-256 fingerprint.
+pub fn validate(settings: &TlsSettings) -> Result<(), String> {
+    client_config(settings).map(|_| ())
+}
+
+fn client_config(settings: &TlsSettings) -> Result<ClientConfig, String> {
+    let provider = Arc::new(crypto::ring::default_provider());
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    if let Some(path) = settings
+        .ca_bundle
+        .as_deref()
+        .filter(|path| !path.trim().is_empty())
+    {
+        let mut added = 0;
+        for cert in CertificateDer::pem_file_iter(path)
+            .map_err(|err| format!("Cannot read CA bundle {path}: {err}"))?
+        {
+            let cert = cert.map_err(|err| format!("Invalid certificate in {path}: {err}"))?;
+            roots
+                .add(cert)
+                .map_err(|err| format!("Invalid CA certificate in {path}: {err}"))?;
+            added += 1;
+        }
+        if added == 0 {
+            return Err(format!("CA bundle {path} contains no PEM certificates"));
+        }
+    }
+    let pins = settings
+        .pinned_certs
+        .iter()
+        .map(|pin| parse_fingerprint(pin))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| err.to_string())?;
+    if pins.is_empty() {
+        return Ok(builder.with_root_certificates(roots).with_no_client_auth());
+    }
+    Ok(builder
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedVerifier { pins, provider }))
+        .with_no_client_auth())
+}
+
+/// Parses `AB:CD:…`, `abcd…` or `sha256:abcd…` into the 32 fingerprint bytes.
+pub fn parse_fingerprint(text: &str) -> Result<[u8; 32], String> {
+    let trimmed = text.trim();
+    let hex: String = trimmed
+        .strip_prefix("sha256:")
+        .or_else(|| trimmed.strip_prefix("SHA256:"))
+        .unwrap_or(trimmed)
+        .chars()
+        .filter(|ch| *ch != ':' && !ch.is_whitespace())
+        .collect();
+    let invalid = || format!("Pinned certificate \"{text}\" is not a SHA-256 fingerprint");
+    // `from_str_radix` alone would also take a leading `+`.
+    if hex.len() != 64 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let mut bytes = [0u8; 32];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+/// Formats a certificate's SHA-256 fingerprint the way `openssl x509 -fingerprint` does.
+pub fn fingerprint(cert: &[u8]) -> String {
+    Sha256::digest(cert)
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Tokens may only travel over HTTPS, or over plain HTTP to this machine.
+pub fn may_send_token(url: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    match url.scheme() {
+        "https" => true,
+        "http" => match url.host() {
+            Some(Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+            Some(Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Explains a failed request when a TLS problem caused it.
+pub(crate) fn describe(err: &(dyn StdError + 'static)) -> Option<String> {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        let tls = err.downcast_ref::<rustls::Error>().or_else(|| {
+            err.downcast_ref::<std::io::Error>()
+                .and_then(|io| io.get_ref())
+                .and_then(|inner| inner.downcast_ref::<rustls::Error>())
+        });
+        if let Some(tls) = tls {
+            return Some(explain(tls));
+        }
+        current = err.source();
+    }
+    None
+}
+
+fn explain(err: &rustls::Error) -> String {
+    let rustls::Error::InvalidCertificate(cert) = err else {
+        return format!("TLS handshake failed: {err}");
+    };
+    let reason = match cert {
+        CertificateError::UnknownIssuer => {
+            "it is not signed by a trusted certificate authority (self-signed?)".to_string()
+        }
+        other => other.to_string(),
+    };
+    format!(
+        "The server's certificate was rejected: {reason}. \
+         Add its CA to the profile's CA bundle or pin the certificate's SHA-256 fingerprint"
+    )
+}
+
+/// Accepts a server whose leaf certificate matches a pin, whoever signed it.
+#[derive(Debug)]
+struct PinnedVerifier {
+    pins: Vec<[u8; 32]>,
+    provider: Arc<CryptoProvider>,
+}
+
+#[derive(Debug)]
+struct PinMismatch(String);
+
+impl fmt::Display for PinMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "its SHA-256 fingerprint {} matches none of the pinned certificates",
+            self.0
+        )
+    }
+}
+
+impl StdError for PinMismatch {}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let digest: [u8; 32] = Sha256::digest(end_entity.as_ref()).into();
+        if self.pins.contains(&digest) {
+            return Ok(ServerCertVerified::assertion());
+        }
+        Err(rustls::Error::InvalidCertificate(CertificateError::Other(
+            rustls::OtherError(Arc::new(PinMismatch(fingerprint(end_entity.as_ref())))),
+        )))
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn expected() -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef][index % 8];
+        }
+        bytes
+    }
+
+    #[test]
+    fn parses_fingerprints_in_every_notation() {
+        let colons = HEX
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| std::str::from_utf8(pair).unwrap())
+            .collect::<Vec<_>>()
+            .join(":");
+        for text in [
+            HEX.to_string(),
+            HEX.to_uppercase(),
+            colons.clone(),
+            colons.to_uppercase(),
+            format!("sha256:{HEX}"),
+            format!("SHA256:{colons}"),
+            format!("  {colons}\n"),
+        ] {
+            assert_eq!(parse_fingerprint(&text).unwrap(), expected(), "{text}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_fingerprints() {
+        assert!(parse_fingerprint(&HEX[..62]).is_err());
+        assert!(parse_fingerprint(&format!("{HEX}00")).is_err());
+        assert!(parse_fingerprint(&HEX.replace('0', "g")).is_err());
+        assert!(parse_fingerprint(&"+a".repeat(32)).is_err());
+        assert!(parse_fingerprint("").is_err());
+        // A SHA-1 fingerprint is too short.
+        assert!(parse_fingerprint(&HEX[..40]).is_err());
+    }
+
+    #[test]
+    fn formats_fingerprints_that_parse_back() {
+        let formatted = fingerprint(b"certificate");
+        assert_eq!(formatted.len(), 32 * 3 - 1);
+        let bytes = parse_fingerprint(&formatted).unwrap();
+        assert_eq!(bytes.as_slice(), Sha256::digest(b"certificate").as_slice());
+    }
+
+    #[test]
+    fn sends_tokens_over_plain_http_only_to_this_machine() {
+        assert!(may_send_token("https://ctx.example.com"));
+        assert!(may_send_token("http://127.0.0.1:5173"));
+        assert!(may_send_token("http://127.8.0.1"));
+        assert!(may_send_token("http://localhost:5173"));
+        assert!(may_send_token("http://LOCALHOST"));
+        assert!(may_send_token("http://[::1]:5173"));
+        assert!(!may_send_token("http://ctx.example.com"));
+        assert!(!may_send_token("http://192.168.1.20:5173"));
+        assert!(!may_send_token("http://localhost.example.com"));
+        assert!(!may_send_token("ftp://127.0.0.1"));
+        assert!(!may_send_token("not a url"));
+    }
+}
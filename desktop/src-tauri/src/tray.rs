@@ -0,0 +1,275 @@
+use std::sync::Mutex;
+
+use context_cache_desktop_lib::dto::{IngestRequest, SourceResponse};
+use context_cache_desktop_lib::settings::Settings;
+use tauri::image::Image;
+use tauri::{
+    menu::{
+        CheckMenuItem, CheckMenuItemBuilder, MenuBuilder, MenuEvent, MenuItem, MenuItemBuilder,
+        Submenu,
+    },
+    tray::{TrayIcon, TrayIconBuilder},
+    AppHandle, Manager, Wry,
+};
+
+use crate::health::BackendState;
+use crate::pause::{self, PauseFor};
+use crate::{add_source, app_settings, clippings, ingest, open_ui};
+
+const TRAY_ICON: &[u8] = include_bytes!("../icons/tray.png");
+const INGEST_SOURCE_PREFIX: &str = "ingest_source:";
+const PROFILE_PREFIX: &str = "profile:";
+const PAUSE_FOR_PREFIX: &str = "pause_for:";
+
+/// Tray pieces that change at runtime.
+pub struct TrayHandles {
+    tray: TrayIcon<Wry>,
+    ingest_sources: Submenu<Wry>,
+    profiles: Submenu<Wry>,
+    pause: CheckMenuItem<Wry>,
+    /// `(id, label)` pairs currently shown in the per-source submenu.
+    listed_sources: Mutex<Option<Vec<(String, String)>>>,
+    backend_state: Mutex<BackendState>,
+    /// Second tooltip line from the scheduler, e.g. "Next run: Notes at 14:20".
+    next_run: Mutex<Option<String>>,
+    /// Tooltip line shown while indexing is paused.
+    paused: Mutex<Option<String>>,
+}
+
+pub fn init_tray(app: &AppHandle) -> tauri::Result<()> {
+    let open_item = MenuItemBuilder::with_id("open", "Open UI").build(app)?;
+    let ingest_item = MenuItemBuilder::with_id("ingest", "Ingest Now").build(app)?;
+    let ingest_sources = Submenu::with_id(app, "ingest_sources", "Ingest Source", true)?;
+    ingest_sources.append(&placeholder_item(app, "No sources loaded")?)?;
+    let add_source_item =
+        MenuItemBuilder::with_id("add_source", "Add Folder as Source…").build(app)?;
+    let save_clipboard_item =
+        MenuItemBuilder::with_id("save_clipboard", "Save Clipboard to Context Cache").build(app)?;
+    let pause_item = CheckMenuItemBuilder::with_id("pause", "Pause Indexing").build(app)?;
+    let pause_for = Submenu::with_id(app, "pause_for", "Pause Indexing For", true)?;
+    for (id, label) in [
+        ("30m", "30 Minutes"),
+        ("1h", "1 Hour"),
+        ("tomorrow", "Until Tomorrow"),
+    ] {
+        pause_for.append(
+            &MenuItemBuilder::with_id(format!("{PAUSE_FOR_PREFIX}{id}"), label).build(app)?,
+        )?;
+    }
+    let profiles = Submenu::with_id(app, "profiles", "Backend", true)?;
+    let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
+
+    let menu = MenuBuilder::new(app)
+        .item(&open_item)
+        .item(&ingest_item)
+        .item(&ingest_sources)
+        .item(&add_source_item)
+        .item(&save_clipboard_item)
+        .separator()
+        .item(&pause_item)
+        .item(&pause_for)
+        .separator()
+        .item(&profiles)
+        .item(&quit_item)
+        .build()?;
+
+    let tray_icon = Image::from_bytes(TRAY_ICON)?;
+
+    let tray = TrayIconBuilder::new()
+        .icon(tray_icon)
+        .tooltip("Context Cache")
+        .menu(&menu)
+        .on_menu_event(|app, event: MenuEvent| match event.id().as_ref() {
+            "open" => {
+                if let Err(err) = open_ui(app.clone()) {
+                    eprintln!("Failed to open UI: {err}");
+                }
+            }
+            "ingest" => ingest::spawn_ingest(app, IngestRequest::all()),
+            "add_source" => add_source::pick_folder(app),
+            "save_clipboard" => clippings::capture(app),
+            "pause" => pause::toggle(app),
+            "quit" => {
+                app.exit(0);
+            }
+            id => {
+                if let Some(source_id) = id.strip_prefix(INGEST_SOURCE_PREFIX) {
+                    let request = IngestRequest {
+                        sources: Some(vec![source_id.to_string()]),
+                        ..IngestRequest::default()
+                    };
+                    ingest::spawn_ingest(app, request);
+                } else if let Some(duration) = id
+                    .strip_prefix(PAUSE_FOR_PREFIX)
+                    .and_then(PauseFor::from_id)
+                {
+                    pause::pause(app, duration);
+                } else if let Some(name) = id.strip_prefix(PROFILE_PREFIX) {
+                    if let Err(err) = app_settings::switch_profile(app.clone(), name.to_string()) {
+                        eprintln!("Failed to switch backend profile: {err}");
+                    }
+                }
+            }
+        })
+        .build(app)?;
+
+    app.manage(TrayHandles {
+        tray,
+        ingest_sources,
+        profiles,
+        pause: pause_item,
+        listed_sources: Mutex::new(None),
+        backend_state: Mutex::new(BackendState::Starting),
+        next_run: Mutex::new(None),
+        paused: Mutex::new(None),
+    });
+    show_profiles(app, &app_settings::current(app))
+}
+
+/// Rebuilds the "Backend" submenu, checking the active profile.
+pub fn show_profiles(app: &AppHandle, settings: &Settings) -> tauri::Result<()> {
+    let submenu = &app.state::<TrayHandles>().profiles;
+    while submenu.remove_at(0)?.is_some() {}
+    let active = &settings.active().name;
+    for profile in &settings.profiles {
+        let item = CheckMenuItemBuilder::with_id(
+            format!("{PROFILE_PREFIX}{}", profile.name),
+            &profile.name,
+        )
+        .checked(&profile.name == active)
+        .build(app)?;
+        submenu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// Rebuilds the per-source submenu when `sources` differs from what is shown.
+pub fn show_sources(app: &AppHandle, sources: &[SourceResponse]) -> tauri::Result<()> {
+    let handles = app.state::<TrayHandles>();
+    let entries: Vec<(String, String)> = sources
+        .iter()
+        .map(|source| {
+            let label = source.label.clone().unwrap_or_else(|| source.uri.clone());
+            (source.id.clone(), label)
+        })
+        .collect();
+    let mut listed = handles.listed_sources.lock().unwrap();
+    if listed.as_ref() == Some(&entries) {
+        return Ok(());
+    }
+
+    let submenu = &handles.ingest_sources;
+    while submenu.remove_at(0)?.is_some() {}
+    if entries.is_empty() {
+        submenu.append(&placeholder_item(app, "No sources registered")?)?;
+    }
+    for (id, label) in &entries {
+        let item =
+            MenuItemBuilder::with_id(format!("{INGEST_SOURCE_PREFIX}{id}"), label).build(app)?;
+        submenu.append(&item)?;
+    }
+    *listed = Some(entries);
+    Ok(())
+}
+
+fn placeholder_item(app: &AppHandle, text: &str) -> tauri::Result<MenuItem<Wry>> {
+    MenuItemBuilder::new(text).enabled(false).build(app)
+}
+
+pub fn apply_backend_state(app: &AppHandle, state: BackendState) -> tauri::Result<()> {
+    let handles = app.state::<TrayHandles>();
+    // Ingest items stay enabled while the backend is unreachable; the outbox queues the request.
+    let icon = match state.badge_color() {
+        Some(color) => badged_icon(color)?,
+        None => Image::from_bytes(TRAY_ICON)?,
+    };
+    handles.tray.set_icon(Some(icon))?;
+    *handles.backend_state.lock().unwrap() = state;
+    update_tooltip(app)
+}
+
+/// Shows `line` under the backend state in the tooltip; `None` removes it.
+pub fn show_next_run(app: &AppHandle, line: Option<String>) -> tauri::Result<()> {
+    let handles = app.state::<TrayHandles>();
+    {
+        let mut next_run = handles.next_run.lock().unwrap();
+        if *next_run == line {
+            return Ok(());
+        }
+        *next_run = line;
+    }
+    update_tooltip(app)
+}
+
+/// Checks "Pause Indexing" and shows `line` in the tooltip while paused; `None` means running.
+pub fn show_paused(app: &AppHandle, line: Option<String>) -> tauri::Result<()> {
+    let handles = app.state::<TrayHandles>();
+    // The check item toggles itself on click, so set it even when nothing else changed.
+    handles.pause.set_checked(line.is_some())?;
+    *handles.paused.lock().unwrap() = line;
+    update_tooltip(app)
+}
+
+fn update_tooltip(app: &AppHandle) -> tauri::Result<()> {
+    let handles = app.state::<TrayHandles>();
+    let state = *handles.backend_state.lock().unwrap();
+    let settings = app_settings::current(app);
+    let mut tooltip = if settings.profiles.len() > 1 {
+        format!(
+            "Context Cache ({}) — {}",
+            settings.active().name,
+            state.label()
+        )
+    } else {
+        format!("Context Cache — {}", state.label())
+    };
+    if let Some(line) = handles.paused.lock().unwrap().as_deref() {
+        tooltip.push('\n');
+        tooltip.push_str(line);
+    }
+    if let Some(line) = handles.next_run.lock().unwrap().as_deref() {
+        tooltip.push('\n');
+        tooltip.push_str(line);
+    }
+    handles.tray.set_tooltip(Some(tooltip))
+}
+
+/// Label of a source as listed in the tray, if the list has been loaded.
+pub fn source_label(app: &AppHandle, id: &str) -> Option<String> {
+    let handles = app.state::<TrayHandles>();
+    let listed = handles.listed_sources.lock().unwrap();
+    listed
+        .as_ref()?
+        .iter()
+        .find(|(source_id, _)| source_id == id)
+        .map(|(_, label)| label.clone())
+}
+
+/// Draws a status dot into the bottom-right corner of the tray icon.
+fn badged_icon(color: [u8; 3]) -> tauri::Result<Image<'static>> {
+    let base = Image::from_bytes(TRAY_ICON)?;
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = width.min(height) as f32 * 0.22;
+    let border = (radius * 0.18).max(1.0);
+    let (cx, cy) = (width as f32 - radius, height as f32 - radius);
+    let left = (cx - radius).max(0.0) as u32;
+    let top = (cy - radius).max(0.0) as u32;
+    for y in top..height {
+        for x in left..width {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let [r, g, b] = if distance <= radius - border {
+                color
+            } else if distance <= radius {
+                [255, 255, 255]
+            } else {
+                continue;
+            };
+            let offset = ((y * width + x) * 4) as usize;
+            rgba[offset..offset + 4].copy_from_slice(&[r, g, b, 255]);
+        }
+    }
+    Ok(Image::new_owned(rgba, width, height))
+}
@@ -0,0 +1,239 @@
+//! Optional filesystem watcher in the shell that ingests edited files by path.
+//!
+//! Complements the backend's own watcher, which only runs inside the backend process.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use context_cache_desktop_lib::dto::{IngestRequest, SourceResponse};
+use context_cache_desktop_lib::globs::SourceFilter;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Manager};
+
+use crate::outbox::Sent;
+use crate::{app_settings, ingest, jobs, pause, sources};
+
+/// A burst of events is flushed at the latest this many debounce periods after it began.
+const MAX_DELAY_FACTOR: u32 = 10;
+/// Paths per `/ingest` request, so a mass rename does not become one huge request.
+const BATCH_SIZE: usize = 200;
+
+/// One watched source folder.
+struct WatchedSource {
+    id: String,
+    root: PathBuf,
+    filter: SourceFilter,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Dropping the watcher stops it.
+    watcher: Option<RecommendedWatcher>,
+    /// `(id, root, include, exclude)` of what is watched, to skip needless rebuilds.
+    watched_key: Vec<(String, PathBuf, Option<String>, Option<String>)>,
+    sources: Vec<WatchedSource>,
+    /// Latest `/sources` listing, kept so a settings change can start the watcher at once.
+    listing: Vec<SourceResponse>,
+}
+
+pub struct ShellWatcher {
+    inner: Mutex<Inner>,
+    events: Sender<PathBuf>,
+}
+
+pub fn init(app: &AppHandle) {
+    let (events, receiver) = mpsc::channel();
+    app.manage(ShellWatcher {
+        inner: Mutex::new(Inner::default()),
+        events,
+    });
+    let app = app.clone();
+    thread::spawn(move || debounce(&app, receiver));
+}
+
+/// Watches the folders of `listing` when the watcher is enabled, and nothing otherwise.
+pub fn sync(app: &AppHandle, listing: &[SourceResponse]) {
+    let state = app.state::<ShellWatcher>();
+    let mut inner = state.inner.lock().unwrap();
+    inner.listing = listing.to_vec();
+    let settings = app_settings::current(app);
+    let wanted: Vec<_> = if settings.watcher.enabled {
+        listing
+            .iter()
+            .filter_map(|source| {
+                let root = sources::expand_path(&source.uri).ok()?;
+                root.is_dir().then(|| {
+                    (
+                        source.id.clone(),
+                        root,
+                        source.include_glob.clone(),
+                        source.exclude_glob.clone(),
+                    )
+                })
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if wanted == inner.watched_key {
+        return;
+    }
+    inner.watcher = None;
+    inner.sources.clear();
+    inner.watched_key.clear();
+    if wanted.is_empty() {
+        return;
+    }
+    let events = state.events.clone();
+    let mut watcher =
+        match notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+            Ok(event) if is_change(&event.kind) => {
+                for path in event.paths {
+                    let _ = events.send(path);
+                }
+            }
+            Ok(_) => {}
+            Err(err) => eprintln!("Filesystem watcher error: {err}"),
+        }) {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                eprintln!("Failed to start the filesystem watcher: {err}");
+                return;
+            }
+        };
+    inner.watched_key = wanted.clone();
+    for (id, root, include, exclude) in wanted {
+        let filter = match SourceFilter::new(include.as_deref(), exclude.as_deref()) {
+            Ok(filter) => filter,
+            Err(err) => {
+                eprintln!("Not watching source {id}: {err}");
+                continue;
+            }
+        };
+        if let Err(err) = watcher.watch(&root, RecursiveMode::Recursive) {
+            eprintln!("Failed to watch {}: {err}", root.display());
+            continue;
+        }
+        inner.sources.push(WatchedSource { id, root, filter });
+    }
+    inner.watcher = Some(watcher);
+}
+
+/// Re-applies the last listing after the watcher setting changed.
+pub fn apply_settings(app: &AppHandle) {
+    let listing = app
+        .state::<ShellWatcher>()
+        .inner
+        .lock()
+        .unwrap()
+        .listing
+        .clone();
+    sync(app, &listing);
+}
+
+fn is_change(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Any
+    )
+}
+
+/// Collects changed paths until events pause for the debounce period, then ingests them.
+fn debounce(app: &AppHandle, receiver: Receiver<PathBuf>) {
+    let mut pending = BTreeSet::new();
+    let mut burst_started: Option<Instant> = None;
+    loop {
+        let quiet = Duration::from_millis(app_settings::current(app).watcher.debounce_ms.max(1));
+        match receiver.recv_timeout(quiet) {
+            Ok(path) => {
+                if accepted(app, &path) {
+                    pending.insert(path);
+                    burst_started.get_or_insert_with(Instant::now);
+                }
+                // Keep coalescing unless the burst has gone on too long.
+                if burst_started.is_none_or(|started| started.elapsed() < quiet * MAX_DELAY_FACTOR)
+                {
+                    continue;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        // While paused, changes pile up and are ingested once indexing resumes.
+        if pending.is_empty() || pause::is_paused(app) {
+            continue;
+        }
+        burst_started = None;
+        // Deleted or renamed-away files have nothing left to ingest.
+        let paths: Vec<String> = std::mem::take(&mut pending)
+            .into_iter()
+            .filter(|path| path.is_file())
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        for (index, batch) in paths.chunks(BATCH_SIZE).enumerate() {
+            if !flush(app, batch.to_vec()) {
+                // An ingest of the same sources is running; try again after the next quiet period.
+                pending.extend(paths[index * BATCH_SIZE..].iter().map(PathBuf::from));
+                break;
+            }
+        }
+    }
+}
+
+/// True when some watched source includes `path`.
+fn accepted(app: &AppHandle, path: &Path) -> bool {
+    let state = app.state::<ShellWatcher>();
+    let inner = state.inner.lock().unwrap();
+    inner
+        .sources
+        .iter()
+        .filter(|source| path.starts_with(&source.root))
+        .any(|source| source.filter.accepts(path))
+}
+
+/// Ingests one batch quietly; returns false, without ingesting, while an overlapping ingest runs.
+///
+/// Only batches that fail, or have files that fail, are reported, the way a manual ingest
+/// reports its outcome.
+fn flush(app: &AppHandle, paths: Vec<String>) -> bool {
+    let owners = owning_sources(app, &paths);
+    let request = IngestRequest {
+        paths: Some(paths),
+        ..IngestRequest::default()
+    };
+    let Ok(_in_flight) = ingest::claim_within(app, &request, &owners) else {
+        return false;
+    };
+    let client = crate::client(app);
+    let reported = match jobs::run(app, &client, &request) {
+        Ok((job_id, Sent::Done(response))) if response.stats.failed > 0 => {
+            ingest::report_finished(app, Some(&job_id), &response)
+        }
+        Ok(_) => Ok(()),
+        Err(err) => ingest::report_failed(app, &err),
+    };
+    if let Err(err) = reported {
+        eprintln!("Failed to report watcher ingest: {err}");
+    }
+    true
+}
+
+/// Ids of the watched sources that hold any of `paths`.
+fn owning_sources(app: &AppHandle, paths: &[String]) -> Vec<String> {
+    let state = app.state::<ShellWatcher>();
+    let inner = state.inner.lock().unwrap();
+    inner
+        .sources
+        .iter()
+        .filter(|source| {
+            paths
+                .iter()
+                .any(|path| Path::new(path).starts_with(&source.root))
+        })
+        .map(|source| source.id.clone())
+        .collect()
+}
@@ -1,10 +1,14 @@
 import { useEffect, useMemo, useState } from "react";
-import { Link, Navigate, Route, Routes, useLocation } from "react-router-dom";
+import { Link, Navigate, Route, Routes, useLocation, useNavigate } from "react-router-dom";
 
+import FileDropZone from "./components/FileDropZone";
+import AddSourcePage from "./pages/AddSource";
+import QuickSearchPage from "./pages/QuickSearch";
 import StatusPage from "./pages/Status";
 import SearchPage from "./pages/Search";
 import SettingsPage from "./pages/Settings";
-import { useBackendHost } from "./hooks/useApi";
+import WhyPage from "./pages/Why";
+import { tauriInvoke, tauriListen, useBackendHost } from "./hooks/useApi";
 import logoAsset from "./assets/logo.png";
 
 const NAV_ITEMS = [
@@ -93,11 +97,55 @@ function Header() {
   );
 }
 
+interface Navigation {
+  path: string;
+}
+
+/** Follows `ctxc://` links the shell receives, including the one the app was launched with. */
+function DeepLinkNavigator() {
+  const navigate = useNavigate();
+
+  useEffect(() => {
+    const take = () =>
+      tauriInvoke()?.<Navigation | null>("take_pending_navigation").then((pending) => {
+        if (pending) {
+          navigate(pending.path);
+        }
+      });
+    void take();
+    const unlisten = tauriListen<Navigation>("navigate", () => void take());
+    return () => {
+      unlisten?.then((stop) => stop());
+    };
+  }, [navigate]);
+
+  return null;
+}
+
 export default function App() {
   const defaultRoute = useMemo(() => NAV_ITEMS[0].path, []);
+  const location = useLocation();
+
+  useEffect(() => {
+    // Every page caches data from the previous backend; start over against the new one.
+    const unlisten = tauriListen("profile-changed", () => window.location.reload());
+    return () => {
+      unlisten?.then((stop) => stop());
+    };
+  }, []);
+
+  if (location.pathname === "/add-source") {
+    return <AddSourcePage />;
+  }
+
+  if (location.pathname === "/quick-search") {
+    return <QuickSearchPage />;
+  }
 
   return (
     <div className="layout">
+      <DeepLinkNavigator />
+      <FileDropZone />
       <Sidebar />
       <div className="content-area">
         <Header />
@@ -106,6 +154,7 @@ export default function App() {
             <Route path="/status" element={<StatusPage />} />
             <Route path="/search" element={<SearchPage />} />
             <Route path="/settings" element={<SettingsPage />} />
+            <Route path="/why/:queryId" element={<WhyPage />} />
             <Route path="/" element={<Navigate to={defaultRoute} replace />} />
           </Routes>
         </main>
@@ -0,0 +1,71 @@
+import { useEffect, useState } from "react";
+
+import { tauriListen } from "../hooks/useApi";
+import type { DropResults } from "../types";
+
+const STATUS_LABELS: Record<string, string> = {
+  processed: "Indexed",
+  skipped: "Unchanged",
+  error: "Failed",
+  unsupported: "Unsupported"
+};
+
+/** Overlay shown while files hover the window, then a per-file report once the shell ingests them. */
+export default function FileDropZone() {
+  const [hovering, setHovering] = useState(false);
+  const [pending, setPending] = useState(false);
+  const [report, setReport] = useState<DropResults | null>(null);
+
+  useEffect(() => {
+    const stopHover = tauriListen<{ active: boolean }>("file-drop-hover", ({ active }) => {
+      setHovering(active);
+    });
+    const stopDrop = tauriListen("file-drop-started", () => {
+      setPending(true);
+      setReport(null);
+    });
+    const stopResults = tauriListen<DropResults>("file-drop-results", (results) => {
+      setPending(false);
+      setReport(results);
+    });
+    return () => {
+      for (const unlisten of [stopHover, stopDrop, stopResults]) {
+        unlisten?.then((stop) => stop());
+      }
+    };
+  }, []);
+
+  return (
+    <>
+      {hovering && (
+        <div className="overlay drop-overlay">
+          <p>Drop files or folders to index them</p>
+        </div>
+      )}
+      {(pending || report) && (
+        <section className="panel drop-report" aria-live="polite">
+          <header className="drawer-header">
+            <h3>{pending ? "Indexing dropped files…" : "Dropped files"}</h3>
+            {report && (
+              <button className="button-outline" type="button" onClick={() => setReport(null)}>
+                Dismiss
+              </button>
+            )}
+          </header>
+          {report?.error && <p className="status-note">{report.error}</p>}
+          {report && report.files.length > 0 && (
+            <ul className="drop-report-files">
+              {report.files.map((file) => (
+                <li key={`${file.status}:${file.path}`} className={`drop-status-${file.status}`}>
+                  <strong>{STATUS_LABELS[file.status] ?? file.status}</strong>
+                  <span title={file.path}>{file.path.split(/[\\/]/).pop() || file.path}</span>
+                  {file.detail && <small>{file.detail}</small>}
+                </li>
+              ))}
+            </ul>
+          )}
+        </section>
+      )}
+    </>
+  );
+}
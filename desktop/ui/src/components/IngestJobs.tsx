@@ -0,0 +1,101 @@
+import { Fragment, useEffect, useState } from "react";
+
+import { tauriInvoke, tauriListen } from "../hooks/useApi";
+import type { IngestJob } from "../types";
+
+const STATUS_LABELS: Record<IngestJob["status"], string> = {
+  running: "Running",
+  queued: "Queued",
+  completed: "Completed",
+  failed: "Failed"
+};
+
+function describeStatus(job: IngestJob) {
+  if (job.status === "running" && job.steps_total > 1) {
+    return `Running (${job.steps_done} of ${job.steps_total})`;
+  }
+  return STATUS_LABELS[job.status];
+}
+
+/** Ingests started from the desktop app, newest first, updated live while they run. */
+export default function IngestJobs() {
+  const [jobs, setJobs] = useState<IngestJob[]>([]);
+  const [expanded, setExpanded] = useState<string | null>(null);
+
+  useEffect(() => {
+    tauriInvoke()?.<IngestJob[]>("list_ingest_jobs").then(setJobs).catch(() => undefined);
+    const unlisten = tauriListen<IngestJob>("ingest-progress", (job) => {
+      setJobs((current) =>
+        current.some((entry) => entry.id === job.id)
+          ? current.map((entry) => (entry.id === job.id ? job : entry))
+          : [job, ...current]
+      );
+    });
+    return () => {
+      unlisten?.then((stop) => stop());
+    };
+  }, []);
+
+  if (jobs.length === 0) {
+    return null;
+  }
+
+  return (
+    <section className="panel">
+      <h3 style={{ marginTop: 0 }}>Recent ingests</h3>
+      <p className="panel-subtitle">Ingests started from the tray, schedules, the folder watcher or dropped files.</p>
+      <div className="sources-table-wrapper" style={{ marginTop: "1.25rem" }}>
+        <table className="table">
+          <thead>
+            <tr>
+              <th>Target</th>
+              <th>Started</th>
+              <th>Status</th>
+              <th>Processed</th>
+              <th>Failed</th>
+            </tr>
+          </thead>
+          <tbody>
+            {jobs.map((job) => (
+              <Fragment key={job.id}>
+                <tr>
+                  <td>{job.label}</td>
+                  <td>{new Date(job.started_at).toLocaleString()}</td>
+                  <td title={job.error ?? undefined}>{describeStatus(job)}</td>
+                  <td>{job.stats.processed}</td>
+                  <td>
+                    {job.failed_files.length > 0 ? (
+                      <button
+                        className="button-outline"
+                        type="button"
+                        onClick={() => setExpanded(expanded === job.id ? null : job.id)}
+                      >
+                        {job.stats.failed}
+                      </button>
+                    ) : (
+                      job.stats.failed
+                    )}
+                  </td>
+                </tr>
+                {expanded === job.id && (
+                  <tr>
+                    <td colSpan={5}>
+                      <ul style={{ margin: 0, paddingLeft: "1.2rem" }}>
+                        {job.failed_files.map((file, index) => (
+                          <li key={index} style={{ wordBreak: "break-word" }}>
+                            {file.path}
+                            {file.detail ? ` — ${file.detail}` : ""}
+                          </li>
+                        ))}
+                      </ul>
+                    </td>
+                  </tr>
+                )}
+              </Fragment>
+            ))}
+          </tbody>
+        </table>
+      </div>
+    </section>
+  );
+}
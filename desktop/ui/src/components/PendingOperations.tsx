@@ -0,0 +1,67 @@
+import { useEffect, useState } from "react";
+
+import { tauriInvoke, tauriListen } from "../hooks/useApi";
+import type { PendingOperation } from "../types";
+
+/** Requests the shell queued while the backend was down; they are sent in order once it is back. */
+export default function PendingOperations() {
+  const [operations, setOperations] = useState<PendingOperation[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    tauriInvoke()?.<PendingOperation[]>("list_pending_operations").then(setOperations).catch(() => undefined);
+    const unlisten = tauriListen<PendingOperation[]>("outbox-changed", setOperations);
+    return () => {
+      unlisten?.then((stop) => stop());
+    };
+  }, []);
+
+  const cancel = async (id: string) => {
+    try {
+      setError(null);
+      await tauriInvoke()?.("cancel_pending_operation", { id });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  if (operations.length === 0) {
+    return null;
+  }
+
+  return (
+    <section className="panel">
+      <h3 style={{ marginTop: 0 }}>Pending operations</h3>
+      <p className="panel-subtitle">
+        The backend was unreachable when these were requested. They run in order as soon as it is back, each on the backend it was meant for.
+      </p>
+      {error && <p style={{ color: "tomato" }}>{error}</p>}
+      <div className="sources-table-wrapper" style={{ marginTop: "1.25rem" }}>
+        <table className="table">
+          <thead>
+            <tr>
+              <th>Operation</th>
+              <th>Backend</th>
+              <th>Queued</th>
+              <th />
+            </tr>
+          </thead>
+          <tbody>
+            {operations.map((operation) => (
+              <tr key={operation.id}>
+                <td title={`${operation.method} ${operation.path}`}>{operation.label}</td>
+                <td>{operation.profile}</td>
+                <td>{new Date(operation.queued_at).toLocaleString()}</td>
+                <td style={{ textAlign: "right" }}>
+                  <button className="button-outline" type="button" onClick={() => cancel(operation.id)}>
+                    Cancel
+                  </button>
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      </div>
+    </section>
+  );
+}
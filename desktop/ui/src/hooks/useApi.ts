@@ -1,6 +1,8 @@
 import { useCallback, useEffect, useState } from "react";
 import axios from "axios";
 
+import type { SettingsView } from "../types";
+
 const DEFAULT_HOST = "http://127.0.0.1:5173";
 
 export function useBackendHost() {
@@ -16,6 +18,15 @@ export function useBackendHost() {
     };
   }, []);
 
+  useEffect(() => {
+    // In the desktop shell the settings file is the source of truth; mirror it into localStorage.
+    tauriInvoke()?.<SettingsView>("get_settings").then(storeBackendUrl).catch(() => undefined);
+    const unlisten = tauriListen<SettingsView>("settings-changed", storeBackendUrl);
+    return () => {
+      unlisten?.then((stop) => stop());
+    };
+  }, []);
+
   return host;
 }
 
@@ -41,7 +52,17 @@ export function useApi<T>(request: () => Promise<T>, deps: unknown[] = []) {
   return { data, loading, error, execute };
 }
 
+function storeBackendUrl(view: SettingsView) {
+  localStorage.setItem("ctxc-host", view.backend_url);
+  window.dispatchEvent(new Event("ctxc-host-changed"));
+}
+
 export async function apiClient(path: string, init?: RequestInit) {
+  const invoke = tauriInvoke();
+  if (invoke) {
+    const body = typeof init?.body === "string" ? JSON.parse(init.body) : undefined;
+    return invoke("backend_request", { method: (init?.method ?? "GET").toUpperCase(), path, body });
+  }
   const host = localStorage.getItem("ctxc-host") || DEFAULT_HOST;
   const url = `${host.replace(/\/$/, "")}${path}`;
   const response = await fetch(url, init);
@@ -52,6 +73,11 @@ export async function apiClient(path: string, init?: RequestInit) {
 }
 
 export async function axiosClient<T>(path: string, method: "get" | "post" | "delete" = "get", body?: unknown) {
+  const invoke = tauriInvoke();
+  if (invoke) {
+    // The shell attaches the API token, so it never has to reach the webview.
+    return invoke<T>("backend_request", { method: method.toUpperCase(), path, body });
+  }
   const host = localStorage.getItem("ctxc-host") || DEFAULT_HOST;
   const baseURL = host.replace(/\/$/, "");
   const response = await axios.request<T>({
@@ -62,3 +88,28 @@ export async function axiosClient<T>(path: string, method: "get" | "post" | "del
   });
   return response.data;
 }
+
+type TauriInvoke = <T>(command: string, args?: Record<string, unknown>) => Promise<T>;
+
+export function tauriInvoke(): TauriInvoke | undefined {
+  return (window as unknown as {
+    __TAURI__?: {
+      core?: {
+        invoke?: TauriInvoke;
+      };
+    };
+  }).__TAURI__?.core?.invoke;
+}
+
+type TauriListen = <T>(event: string, handler: (event: { payload: T }) => void) => Promise<() => void>;
+
+export function tauriListen<T>(event: string, handler: (payload: T) => void): Promise<() => void> | undefined {
+  const listen = (window as unknown as {
+    __TAURI__?: {
+      event?: {
+        listen?: TauriListen;
+      };
+    };
+  }).__TAURI__?.event?.listen;
+  return listen?.<T>(event, (message) => handler(message.payload));
+}
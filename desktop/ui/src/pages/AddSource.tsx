@@ -0,0 +1,92 @@
+import { FormEvent, useEffect, useState } from "react";
+
+import { tauriInvoke } from "../hooks/useApi";
+
+interface SourceDraft {
+  uri: string;
+  label: string;
+}
+
+export default function AddSourcePage() {
+  const [draft, setDraft] = useState<SourceDraft | null>(null);
+  const [label, setLabel] = useState("");
+  const [includeGlob, setIncludeGlob] = useState("");
+  const [excludeGlob, setExcludeGlob] = useState("");
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    tauriInvoke()?.<SourceDraft | null>("get_source_draft").then((value) => {
+      setDraft(value);
+      setLabel(value?.label ?? "");
+    });
+  }, []);
+
+  const submit = async (event: FormEvent) => {
+    event.preventDefault();
+    const invoke = tauriInvoke();
+    if (!draft || !invoke) {
+      return;
+    }
+    try {
+      setBusy(true);
+      setError(null);
+      await invoke("submit_source_draft", {
+        source: {
+          kind: "folder",
+          uri: draft.uri,
+          label: label || undefined,
+          include_glob: includeGlob || undefined,
+          exclude_glob: excludeGlob || undefined
+        }
+      });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <main className="content-scroll">
+      <section className="panel settings-form">
+        <div>
+          <h2 style={{ margin: 0 }}>Add folder as source</h2>
+          <p className="panel-subtitle" style={{ wordBreak: "break-word" }}>
+            {draft ? draft.uri : "No folder selected."}
+          </p>
+        </div>
+        <form onSubmit={submit} className="settings-form">
+          <label className="field">
+            <span>Label</span>
+            <input className="input" value={label} onChange={(event) => setLabel(event.target.value)} />
+          </label>
+          <label className="field">
+            <span>Include glob</span>
+            <input
+              className="input"
+              placeholder="**/*.{md,txt,pdf,docx,eml,mbox}"
+              value={includeGlob}
+              onChange={(event) => setIncludeGlob(event.target.value)}
+            />
+          </label>
+          <label className="field">
+            <span>Exclude glob</span>
+            <input
+              className="input"
+              placeholder="**/{.git,.obsidian,node_modules}/**"
+              value={excludeGlob}
+              onChange={(event) => setExcludeGlob(event.target.value)}
+            />
+          </label>
+          <div style={{ display: "flex", gap: "1rem", alignItems: "center" }}>
+            <button className="button" type="submit" disabled={!draft || busy}>
+              {busy ? "Adding…" : "Add source"}
+            </button>
+            {error && <span style={{ color: "tomato", fontSize: "0.9rem" }}>{error}</span>}
+          </div>
+        </form>
+      </section>
+    </main>
+  );
+}
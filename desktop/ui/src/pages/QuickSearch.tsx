@@ -0,0 +1,119 @@
+import { FormEvent, KeyboardEvent, useEffect, useRef, useState } from "react";
+
+import { tauriInvoke, tauriListen } from "../hooks/useApi";
+import type { ChunkResult, QueryResponse } from "../types";
+
+const SNIPPET_LENGTH = 160;
+
+function resultTitle(result: ChunkResult): string {
+  const meta = result.provenance.meta as { title?: unknown } | undefined;
+  if (typeof meta?.title === "string" && meta.title.trim()) {
+    return meta.title;
+  }
+  const location = String(result.provenance.uri ?? result.provenance.external_id ?? result.document_id);
+  return location.split(/[\\/]/).pop() || location;
+}
+
+function snippet(text: string): string {
+  const flat = text.replace(/\s+/g, " ").trim();
+  return flat.length > SNIPPET_LENGTH ? `${flat.slice(0, SNIPPET_LENGTH)}…` : flat;
+}
+
+export default function QuickSearchPage() {
+  const [query, setQuery] = useState("");
+  const [results, setResults] = useState<ChunkResult[]>([]);
+  const [selected, setSelected] = useState(0);
+  const [loading, setLoading] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [lastQuery, setLastQuery] = useState("");
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    inputRef.current?.focus();
+    const unlisten = tauriListen("quick-search-shown", () => {
+      setMessage(null);
+      inputRef.current?.focus();
+      inputRef.current?.select();
+    });
+    return () => {
+      unlisten?.then((stop) => stop());
+    };
+  }, []);
+
+  const runQuery = async (event: FormEvent) => {
+    event.preventDefault();
+    const invoke = tauriInvoke();
+    if (!invoke || !query.trim()) {
+      return;
+    }
+    try {
+      setLoading(true);
+      setMessage(null);
+      const payload = await invoke<QueryResponse>("query", { query });
+      setResults(payload.results);
+      setLastQuery(query);
+      setSelected(0);
+      if (payload.results.length === 0) {
+        setMessage("No results.");
+      }
+    } catch (err) {
+      setMessage(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const activate = async (result: ChunkResult, alternate: boolean) => {
+    try {
+      await tauriInvoke()?.("activate_result", { result, alternate });
+    } catch (err) {
+      setMessage(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  const onKeyDown = (event: KeyboardEvent) => {
+    if (event.key === "Escape") {
+      event.preventDefault();
+      void tauriInvoke()?.("hide_quick_search");
+    } else if (event.key === "ArrowDown" && results.length > 0) {
+      event.preventDefault();
+      setSelected((index) => Math.min(index + 1, results.length - 1));
+    } else if (event.key === "ArrowUp" && results.length > 0) {
+      event.preventDefault();
+      setSelected((index) => Math.max(index - 1, 0));
+    } else if (event.key === "Enter" && results[selected] && query === lastQuery) {
+      // Enter searches when the query changed and acts on the selected result otherwise.
+      event.preventDefault();
+      void activate(results[selected], event.ctrlKey || event.metaKey);
+    }
+  };
+
+  return (
+    <main className="quick-search" onKeyDown={onKeyDown}>
+      <form onSubmit={runQuery}>
+        <input
+          ref={inputRef}
+          className="input"
+          placeholder="Search your workspace…"
+          value={query}
+          onChange={(event) => setQuery(event.target.value)}
+        />
+      </form>
+      {loading && <p className="status-note">Searching…</p>}
+      {message && <p className="status-note">{message}</p>}
+      <ul className="quick-search-results">
+        {results.map((result, index) => (
+          <li
+            key={result.chunk_id}
+            className={index === selected ? "selected" : ""}
+            onMouseEnter={() => setSelected(index)}
+            onClick={(event) => void activate(result, event.ctrlKey || event.metaKey)}
+          >
+            <strong>{resultTitle(result)}</strong>
+            <span>{snippet(result.text)}</span>
+          </li>
+        ))}
+      </ul>
+    </main>
+  );
+}
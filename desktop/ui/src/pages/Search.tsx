@@ -1,9 +1,10 @@
 import { FormEvent, useEffect, useState } from "react";
+import { useSearchParams } from "react-router-dom";
 
 import Filters from "../components/Filters";
 import ProvenancePanel from "../components/ProvenancePanel";
 import ResultList from "../components/ResultList";
-import { axiosClient } from "../hooks/useApi";
+import { axiosClient, tauriInvoke } from "../hooks/useApi";
 import type { ChunkResult, QueryResponse, Source } from "../types";
 
 export default function SearchPage() {
@@ -14,26 +15,47 @@ export default function SearchPage() {
   const [sources, setSources] = useState<Source[]>([]);
   const [selectedSource, setSelectedSource] = useState<string | null>(null);
   const [selectedResult, setSelectedResult] = useState<ChunkResult | null>(null);
+  const [searchParams, setSearchParams] = useSearchParams();
+  // Set by ctxc://doc/<id>?chunk=<id> and ctxc://chunk/<id> links.
+  const linkedDocument = searchParams.get("document");
+  const linkedChunk = searchParams.get("chunk");
+  // Set when the app is launched or re-launched with `--query <text>`.
+  const launchQuery = searchParams.get("q");
 
   useEffect(() => {
     axiosClient<Source[]>("/sources").then(setSources).catch(() => undefined);
   }, []);
 
-  const runQuery = async (event?: FormEvent) => {
+  useEffect(() => {
+    if (launchQuery) {
+      setQuery(launchQuery);
+      void runQuery(undefined, launchQuery);
+    }
+  }, [launchQuery]);
+
+  const runQuery = async (event?: FormEvent, text: string = query) => {
     event?.preventDefault();
-    if (!query.trim()) {
+    if (!text.trim()) {
       return;
     }
     try {
       setLoading(true);
       setError(null);
-      const payload = await axiosClient<QueryResponse>("/query", "post", {
-        query,
-        k: 8,
-        filters: selectedSource ? { source_ids: [selectedSource] } : undefined
-      });
+      const filters = {
+        ...(selectedSource ? { source_ids: [selectedSource] } : {}),
+        ...(linkedDocument ? { document_ids: [linkedDocument] } : {})
+      };
+      const request = {
+        query: text,
+        filters: Object.keys(filters).length > 0 ? filters : undefined
+      };
+      // The desktop shell fills in k/rerank/hybrid from the saved query defaults.
+      const invoke = tauriInvoke();
+      const payload = invoke
+        ? await invoke<QueryResponse>("query", request)
+        : await axiosClient<QueryResponse>("/query", "post", { ...request, k: 8 });
       setResults(payload.results);
-      setSelectedResult(null);
+      setSelectedResult(payload.results.find((result) => result.chunk_id === linkedChunk) ?? null);
     } catch (err) {
       const message = err instanceof Error ? err.message : String(err);
       setError(message);
@@ -61,6 +83,17 @@ export default function SearchPage() {
             onChange={(event) => setQuery(event.target.value)}
           />
           <Filters sources={sources} selectedSource={selectedSource} onChangeSource={setSelectedSource} />
+          {(linkedDocument || linkedChunk) && (
+            <div className="host-row">
+              <span className="status-note">
+                {linkedDocument ? `Limited to document ${linkedDocument}. ` : ""}
+                {linkedChunk ? `Chunk ${linkedChunk} opens when a search returns it.` : ""}
+              </span>
+              <button className="button-outline" type="button" onClick={() => setSearchParams({})}>
+                Clear
+              </button>
+            </div>
+          )}
           <div style={{ display: "flex", gap: "1rem", alignItems: "center" }}>
             <button className="button" type="submit" disabled={loading}>
               {loading ? "Searching" : "Run search"}
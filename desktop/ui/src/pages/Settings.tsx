@@ -1,7 +1,7 @@
 import { FormEvent, useEffect, useState } from "react";
 
-import { axiosClient } from "../hooks/useApi";
-import type { Source } from "../types";
+import { axiosClient, tauriInvoke, tauriListen } from "../hooks/useApi";
+import type { BackendProfile, DesktopSettings, ScheduleRule, SettingsView, Source } from "../types";
 
 interface SourceFormState {
   uri: string;
@@ -17,6 +17,34 @@ const INITIAL_FORM: SourceFormState = {
   exclude_glob: ""
 };
 
+function toOptionalBool(value: string): boolean | null {
+  return value === "" ? null : value === "on";
+}
+
+function fromOptionalBool(value?: boolean | null): string {
+  return value == null ? "" : value ? "on" : "off";
+}
+
+function activeProfile(settings: DesktopSettings): BackendProfile {
+  return settings.profiles.find((profile) => profile.name === settings.active_profile) ?? settings.profiles[0];
+}
+
+const NETWORK_FIELDS: [keyof DesktopSettings["network"], string][] = [
+  ["connect_timeout_secs", "Connect timeout"],
+  ["health_timeout_secs", "Health check timeout"],
+  ["request_timeout_secs", "Request timeout"],
+  ["ingest_timeout_secs", "Ingest timeout"],
+  ["get_retries", "Retries for failed reads"]
+];
+
+function splitList(value: string): string[] | null {
+  const items = value
+    .split(",")
+    .map((item) => item.trim())
+    .filter(Boolean);
+  return items.length > 0 ? items : null;
+}
+
 export default function SettingsPage() {
   const [host, setHost] = useState(() => localStorage.getItem("ctxc-host") || "http://127.0.0.1:5173");
   const [sources, setSources] = useState<Source[]>([]);
@@ -24,6 +52,11 @@ export default function SettingsPage() {
   const [hostMessage, setHostMessage] = useState<string | null>(null);
   const [ingestMessage, setIngestMessage] = useState<string | null>(null);
   const [ingestBusy, setIngestBusy] = useState(false);
+  const [desktop, setDesktop] = useState<SettingsView | null>(null);
+  const [prefs, setPrefs] = useState<DesktopSettings | null>(null);
+  const [prefsMessage, setPrefsMessage] = useState<string | null>(null);
+  // Token edits by profile position; `null` clears the stored token. Sent once, never read back.
+  const [tokenEdits, setTokenEdits] = useState<Record<number, string | null>>({});
 
   const loadSources = async () => {
     const data = await axiosClient<Source[]>("/sources");
@@ -34,14 +67,127 @@ export default function SettingsPage() {
     loadSources().catch(() => undefined);
   }, []);
 
-  const saveHost = () => {
+  useEffect(() => {
+    const apply = (view: SettingsView) => {
+      setDesktop(view);
+      setPrefs(view.settings);
+      setHost(activeProfile(view.settings).base_url);
+    };
+    tauriInvoke()?.<SettingsView>("get_settings").then(apply).catch(() => undefined);
+    const unlisten = tauriListen<SettingsView>("settings-changed", apply);
+    return () => {
+      unlisten?.then((stop) => stop());
+    };
+  }, []);
+
+  const saveDesktopSettings = async (settings: DesktopSettings, tokens?: Record<string, string | null>) => {
+    const invoke = tauriInvoke();
+    if (!invoke) {
+      return;
+    }
+    const view = await invoke<SettingsView>("set_settings", { settings, tokens: tokens ?? null });
+    setDesktop(view);
+    setPrefs(view.settings);
+  };
+
+  const saveHost = async () => {
     const trimmed = host.trim();
-    localStorage.setItem("ctxc-host", trimmed);
-    window.dispatchEvent(new Event("ctxc-host-changed"));
+    if (desktop) {
+      try {
+        const active = activeProfile(desktop.settings);
+        await saveDesktopSettings({
+          ...desktop.settings,
+          profiles: desktop.settings.profiles.map((profile) =>
+            profile.name === active.name ? { ...profile, base_url: trimmed } : profile
+          )
+        });
+      } catch (err) {
+        setHostMessage(`Could not save host: ${err instanceof Error ? err.message : String(err)}`);
+        return;
+      }
+    } else {
+      localStorage.setItem("ctxc-host", trimmed);
+      window.dispatchEvent(new Event("ctxc-host-changed"));
+    }
     setHostMessage(`Backend host saved (${trimmed}).`);
     window.setTimeout(() => setHostMessage(null), 2400);
   };
 
+  const switchProfile = async (name: string) => {
+    try {
+      const view = await tauriInvoke()?.<SettingsView>("switch_profile", { name });
+      if (view) {
+        setDesktop(view);
+        setPrefs(view.settings);
+      }
+    } catch (err) {
+      setHostMessage(`Could not switch profile: ${err instanceof Error ? err.message : String(err)}`);
+    }
+  };
+
+  const updateProfile = (index: number, changes: Partial<BackendProfile>) => {
+    if (!prefs) {
+      return;
+    }
+    const profiles = prefs.profiles.map((profile, position) => (position === index ? { ...profile, ...changes } : profile));
+    const renamed = prefs.profiles[index].name === prefs.active_profile && changes.name !== undefined;
+    setPrefs({ ...prefs, profiles, active_profile: renamed ? changes.name ?? prefs.active_profile : prefs.active_profile });
+  };
+
+  const addProfile = () => {
+    if (!prefs) {
+      return;
+    }
+    const profile: BackendProfile = {
+      name: `Profile ${prefs.profiles.length + 1}`,
+      base_url: "http://127.0.0.1:5173",
+      filters: {},
+      tls: { ca_bundle: null, pinned_certs: [] }
+    };
+    setPrefs({ ...prefs, profiles: [...prefs.profiles, profile] });
+  };
+
+  const updateSchedule = (changes: Partial<DesktopSettings["schedule"]>) => {
+    if (prefs) {
+      setPrefs({ ...prefs, schedule: { ...prefs.schedule, ...changes } });
+    }
+  };
+
+  const updateRule = (index: number, changes: Partial<ScheduleRule>) => {
+    if (prefs) {
+      updateSchedule({
+        rules: prefs.schedule.rules.map((rule, position) => (position === index ? { ...rule, ...changes } : rule))
+      });
+    }
+  };
+
+  const removeProfile = (index: number) => {
+    if (!prefs || prefs.profiles.length <= 1) {
+      return;
+    }
+    setPrefs({ ...prefs, profiles: prefs.profiles.filter((_, position) => position !== index) });
+    // Positions shift, so pending token edits no longer line up.
+    setTokenEdits({});
+  };
+
+  const savePrefs = async (event: FormEvent) => {
+    event.preventDefault();
+    if (!prefs) {
+      return;
+    }
+    const tokens = Object.fromEntries(
+      Object.entries(tokenEdits).map(([index, token]) => [prefs.profiles[Number(index)].name, token])
+    );
+    try {
+      await saveDesktopSettings(prefs, tokens);
+      setTokenEdits({});
+      setPrefsMessage("Preferences saved.");
+    } catch (err) {
+      setPrefsMessage(`Could not save preferences: ${err instanceof Error ? err.message : String(err)}`);
+    }
+    window.setTimeout(() => setPrefsMessage(null), 2400);
+  };
+
   const submitSource = async (event: FormEvent) => {
     event.preventDefault();
     await axiosClient<Source>("/sources", "post", {
@@ -88,12 +234,31 @@ export default function SettingsPage() {
           <p className="panel-subtitle">Point the desktop client at any reachable Context Cache backend.</p>
         </div>
         <div className="host-row">
+          {desktop && desktop.settings.profiles.length > 1 && (
+            <select
+              className="input"
+              style={{ maxWidth: "12rem" }}
+              value={desktop.settings.active_profile}
+              onChange={(event) => switchProfile(event.target.value)}
+            >
+              {desktop.settings.profiles.map((profile) => (
+                <option key={profile.name} value={profile.name}>
+                  {profile.name}
+                </option>
+              ))}
+            </select>
+          )}
           <input className="input" value={host} onChange={(event) => setHost(event.target.value)} />
           <button className="button" type="button" onClick={saveHost}>
             Save host
           </button>
           {hostMessage && <span className="status-note">{hostMessage}</span>}
         </div>
+        {desktop && desktop.overridden.length > 0 && (
+          <p className="status-note" style={{ margin: 0 }}>
+            Overridden by environment variables: {desktop.overridden.join(", ")}. Connected to {desktop.backend_url}.
+          </p>
+        )}
         <div className="host-row">
           <button className="button-outline" type="button" onClick={() => triggerIngest({ all: true })} disabled={ingestBusy}>
             {ingestBusy ? "Ingesting…" : "Ingest all sources"}
@@ -102,6 +267,379 @@ export default function SettingsPage() {
         </div>
       </section>
 
+      {prefs && (
+        <section className="panel">
+          <div>
+            <h2 style={{ margin: 0 }}>Desktop preferences</h2>
+            <p className="panel-subtitle">Stored in {desktop?.path} and shared with the tray.</p>
+          </div>
+          <form onSubmit={savePrefs} className="settings-form" style={{ marginTop: "1.2rem" }}>
+            <div className="field">
+              <span>Backend profiles (the first one is served by the sidecar when enabled)</span>
+              {prefs.profiles.map((profile, index) => (
+                <div key={index} className="host-row">
+                  <input
+                    className="input"
+                    style={{ maxWidth: "10rem" }}
+                    placeholder="Name"
+                    value={profile.name}
+                    onChange={(event) => updateProfile(index, { name: event.target.value })}
+                  />
+                  <input
+                    className="input"
+                    style={{ maxWidth: "16rem" }}
+                    placeholder="http://127.0.0.1:5173"
+                    value={profile.base_url}
+                    onChange={(event) => updateProfile(index, { base_url: event.target.value })}
+                  />
+                  <input
+                    className="input"
+                    style={{ maxWidth: "10rem" }}
+                    type="password"
+                    placeholder={
+                      tokenEdits[index] === null
+                        ? "Cleared on save"
+                        : desktop?.token_set.includes(profile.name)
+                          ? "Token saved (type to replace)"
+                          : "Auth token"
+                    }
+                    value={tokenEdits[index] ?? ""}
+                    onChange={(event) => {
+                      const value = event.target.value;
+                      setTokenEdits((edits) => {
+                        const next = { ...edits };
+                        if (value) {
+                          next[index] = value;
+                        } else {
+                          delete next[index];
+                        }
+                        return next;
+                      });
+                    }}
+                  />
+                  {desktop?.token_set.includes(profile.name) && tokenEdits[index] === undefined && (
+                    <button
+                      className="button-outline"
+                      type="button"
+                      title="Remove the stored token when preferences are saved"
+                      onClick={() => setTokenEdits((edits) => ({ ...edits, [index]: null }))}
+                    >
+                      Clear token
+                    </button>
+                  )}
+                  <input
+                    className="input"
+                    style={{ maxWidth: "12rem" }}
+                    placeholder="Default tags"
+                    value={(profile.filters.tags ?? []).join(", ")}
+                    onChange={(event) =>
+                      updateProfile(index, { filters: { ...profile.filters, tags: splitList(event.target.value) } })
+                    }
+                  />
+                  <input
+                    className="input"
+                    style={{ maxWidth: "12rem" }}
+                    placeholder="CA bundle (PEM path)"
+                    value={profile.tls.ca_bundle ?? ""}
+                    onChange={(event) =>
+                      updateProfile(index, { tls: { ...profile.tls, ca_bundle: event.target.value || null } })
+                    }
+                  />
+                  <input
+                    className="input"
+                    style={{ maxWidth: "12rem" }}
+                    placeholder="Pinned SHA-256 fingerprints"
+                    title="Only servers presenting a certificate with one of these fingerprints are trusted"
+                    value={profile.tls.pinned_certs.join(", ")}
+                    onChange={(event) =>
+                      updateProfile(index, { tls: { ...profile.tls, pinned_certs: splitList(event.target.value) ?? [] } })
+                    }
+                  />
+                  <button
+                    className="button-outline"
+                    type="button"
+                    onClick={() => removeProfile(index)}
+                    disabled={prefs.profiles.length <= 1}
+                  >
+                    Remove
+                  </button>
+                </div>
+              ))}
+              <div>
+                <button className="button-outline" type="button" onClick={addProfile}>
+                  Add profile
+                </button>
+              </div>
+            </div>
+            <label className="field">
+              <span>Ingest all sources every N minutes (0 to disable)</span>
+              <input
+                className="input"
+                type="number"
+                min={0}
+                value={prefs.auto_ingest_minutes}
+                onChange={(event) => setPrefs({ ...prefs, auto_ingest_minutes: Number(event.target.value) || 0 })}
+              />
+            </label>
+            <div className="field">
+              <span>Scheduled ingests: "every 10m", "every 2h" or a cron expression such as "0 2 * * *"</span>
+              {prefs.schedule.rules.map((rule, index) => (
+                <div key={index} className="host-row">
+                  <select
+                    className="input"
+                    style={{ maxWidth: "14rem" }}
+                    value={rule.source_id ?? ""}
+                    onChange={(event) => updateRule(index, { source_id: event.target.value || null })}
+                  >
+                    <option value="">All sources</option>
+                    {rule.source_id && !sources.some((source) => source.id === rule.source_id) && (
+                      <option value={rule.source_id}>{rule.source_id}</option>
+                    )}
+                    {sources.map((source) => (
+                      <option key={source.id} value={source.id}>
+                        {source.label || source.uri}
+                      </option>
+                    ))}
+                  </select>
+                  <input
+                    className="input"
+                    style={{ maxWidth: "12rem" }}
+                    placeholder="every 10m"
+                    value={rule.schedule}
+                    onChange={(event) => updateRule(index, { schedule: event.target.value })}
+                  />
+                  <button
+                    className="button-outline"
+                    type="button"
+                    onClick={() => updateSchedule({ rules: prefs.schedule.rules.filter((_, position) => position !== index) })}
+                  >
+                    Remove
+                  </button>
+                </div>
+              ))}
+              <div>
+                <button
+                  className="button-outline"
+                  type="button"
+                  onClick={() => updateSchedule({ rules: [...prefs.schedule.rules, { source_id: null, schedule: "every 1h" }] })}
+                >
+                  Add schedule
+                </button>
+              </div>
+            </div>
+            <div className="field">
+              <span>Quiet hours (no scheduled ingest starts in between; leave empty to disable)</span>
+              <div className="host-row">
+                <input
+                  className="input"
+                  style={{ maxWidth: "8rem" }}
+                  type="time"
+                  value={prefs.schedule.quiet_hours?.start ?? ""}
+                  onChange={(event) =>
+                    updateSchedule({
+                      quiet_hours: event.target.value
+                        ? { start: event.target.value, end: prefs.schedule.quiet_hours?.end ?? "07:00" }
+                        : null
+                    })
+                  }
+                />
+                <input
+                  className="input"
+                  style={{ maxWidth: "8rem" }}
+                  type="time"
+                  value={prefs.schedule.quiet_hours?.end ?? ""}
+                  onChange={(event) =>
+                    updateSchedule({
+                      quiet_hours: event.target.value
+                        ? { start: prefs.schedule.quiet_hours?.start ?? "22:00", end: event.target.value }
+                        : null
+                    })
+                  }
+                />
+              </div>
+            </div>
+            <label className="host-row">
+              <input
+                type="checkbox"
+                checked={prefs.schedule.require_ac_power}
+                onChange={(event) => updateSchedule({ require_ac_power: event.target.checked })}
+              />
+              Only run scheduled ingests on AC power
+            </label>
+            <label className="field">
+              <span>Only run scheduled ingests after this many idle minutes (0 to disable)</span>
+              <input
+                className="input"
+                type="number"
+                min={0}
+                value={prefs.schedule.require_idle_minutes}
+                onChange={(event) => updateSchedule({ require_idle_minutes: Number(event.target.value) || 0 })}
+              />
+            </label>
+            <label className="host-row">
+              <input
+                type="checkbox"
+                checked={prefs.watcher.enabled}
+                onChange={(event) => setPrefs({ ...prefs, watcher: { ...prefs.watcher, enabled: event.target.checked } })}
+              />
+              Watch source folders from the desktop app and index changed files
+            </label>
+            <label className="field">
+              <span>Wait this many milliseconds after the last change before indexing</span>
+              <input
+                className="input"
+                type="number"
+                min={100}
+                value={prefs.watcher.debounce_ms}
+                disabled={!prefs.watcher.enabled}
+                onChange={(event) =>
+                  setPrefs({ ...prefs, watcher: { ...prefs.watcher, debounce_ms: Number(event.target.value) || 0 } })
+                }
+              />
+            </label>
+            <div className="field">
+              <span>Timeouts in seconds (connect, health check, requests, ingest) and retries for failed reads</span>
+              <div className="host-row">
+                {NETWORK_FIELDS.map(([key, label]) => (
+                  <input
+                    key={key}
+                    className="input"
+                    style={{ maxWidth: "7rem" }}
+                    type="number"
+                    min={key === "get_retries" ? 0 : 1}
+                    title={label}
+                    value={prefs.network[key]}
+                    onChange={(event) =>
+                      setPrefs({ ...prefs, network: { ...prefs.network, [key]: Number(event.target.value) || 0 } })
+                    }
+                  />
+                ))}
+              </div>
+            </div>
+            <label className="field">
+              <span>Default result count</span>
+              <input
+                className="input"
+                type="number"
+                min={1}
+                value={prefs.query.k}
+                onChange={(event) =>
+                  setPrefs({ ...prefs, query: { ...prefs.query, k: Math.max(1, Number(event.target.value) || 1) } })
+                }
+              />
+            </label>
+            <label className="field">
+              <span>Rerank</span>
+              <select
+                className="input"
+                value={fromOptionalBool(prefs.query.rerank)}
+                onChange={(event) =>
+                  setPrefs({ ...prefs, query: { ...prefs.query, rerank: toOptionalBool(event.target.value) } })
+                }
+              >
+                <option value="">Backend default</option>
+                <option value="on">On</option>
+                <option value="off">Off</option>
+              </select>
+            </label>
+            <label className="field">
+              <span>Hybrid retrieval</span>
+              <select
+                className="input"
+                value={fromOptionalBool(prefs.query.hybrid)}
+                onChange={(event) =>
+                  setPrefs({ ...prefs, query: { ...prefs.query, hybrid: toOptionalBool(event.target.value) } })
+                }
+              >
+                <option value="">Backend default</option>
+                <option value="on">On</option>
+                <option value="off">Off</option>
+              </select>
+            </label>
+            <label className="field">
+              <span>Quick search shortcut (empty to disable)</span>
+              <input
+                className="input"
+                placeholder="CmdOrCtrl+Shift+Space"
+                value={prefs.quick_search.shortcut}
+                onChange={(event) =>
+                  setPrefs({ ...prefs, quick_search: { ...prefs.quick_search, shortcut: event.target.value } })
+                }
+              />
+            </label>
+            <label className="field">
+              <span>Enter in quick search (Ctrl/Cmd+Enter does the other)</span>
+              <select
+                className="input"
+                value={prefs.quick_search.enter_action}
+                onChange={(event) =>
+                  setPrefs({
+                    ...prefs,
+                    quick_search: { ...prefs.quick_search, enter_action: event.target.value as "open" | "copy" }
+                  })
+                }
+              >
+                <option value="open">Open the source document</option>
+                <option value="copy">Copy the provenance link</option>
+              </select>
+            </label>
+            <label className="field">
+              <span>Save clipboard shortcut (empty to disable)</span>
+              <input
+                className="input"
+                placeholder="CmdOrCtrl+Alt+V"
+                value={prefs.clippings.shortcut}
+                onChange={(event) =>
+                  setPrefs({ ...prefs, clippings: { ...prefs.clippings, shortcut: event.target.value } })
+                }
+              />
+            </label>
+            <label className="field">
+              <span>Clippings folder (empty for Documents/Context Cache/Clippings)</span>
+              <input
+                className="input"
+                value={prefs.clippings.folder ?? ""}
+                onChange={(event) =>
+                  setPrefs({ ...prefs, clippings: { ...prefs.clippings, folder: event.target.value || null } })
+                }
+              />
+            </label>
+            <label className="host-row">
+              <input
+                type="checkbox"
+                checked={prefs.notifications.ingest_finished}
+                onChange={(event) =>
+                  setPrefs({
+                    ...prefs,
+                    notifications: { ...prefs.notifications, ingest_finished: event.target.checked }
+                  })
+                }
+              />
+              Notify when an ingest finishes
+            </label>
+            <label className="host-row">
+              <input
+                type="checkbox"
+                checked={prefs.notifications.ingest_failed}
+                onChange={(event) =>
+                  setPrefs({
+                    ...prefs,
+                    notifications: { ...prefs.notifications, ingest_failed: event.target.checked }
+                  })
+                }
+              />
+              Notify when an ingest fails
+            </label>
+            <div className="host-row">
+              <button className="button" type="submit">
+                Save preferences
+              </button>
+              {prefsMessage && <span className="status-note">{prefsMessage}</span>}
+            </div>
+          </form>
+        </section>
+      )}
+
       <div className="settings-grid">
         <section className="panel settings-form">
           <div>
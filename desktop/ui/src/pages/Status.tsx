@@ -1,5 +1,7 @@
 import { useEffect, useRef, useState } from "react";
 
+import IngestJobs from "../components/IngestJobs";
+import PendingOperations from "../components/PendingOperations";
 import { apiClient } from "../hooks/useApi";
 import type { Source } from "../types";
 
@@ -87,6 +89,8 @@ export default function StatusPage() {
           </div>
         </div>
       </section>
+      <PendingOperations />
+      <IngestJobs />
       <section className="panel">
         <h3 style={{ marginTop: 0 }}>Sources</h3>
         <p className="panel-subtitle">
@@ -0,0 +1,43 @@
+import { useEffect, useState } from "react";
+import { useParams } from "react-router-dom";
+
+import ProvenancePanel from "../components/ProvenancePanel";
+import ResultList from "../components/ResultList";
+import { axiosClient, tauriInvoke } from "../hooks/useApi";
+import type { ChunkResult, QueryResponse } from "../types";
+
+export default function WhyPage() {
+  const { queryId = "" } = useParams();
+  const [results, setResults] = useState<ChunkResult[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const [selectedResult, setSelectedResult] = useState<ChunkResult | null>(null);
+
+  useEffect(() => {
+    const invoke = tauriInvoke();
+    const request = invoke
+      ? invoke<QueryResponse>("why", { queryId })
+      : axiosClient<QueryResponse>(`/why/${encodeURIComponent(queryId)}`);
+    setError(null);
+    request
+      .then((payload) => setResults(payload.results))
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }, [queryId]);
+
+  return (
+    <div className="page-section">
+      <section className="panel">
+        <div style={{ marginBottom: "1.25rem" }}>
+          <h2 style={{ margin: 0 }}>Why these results?</h2>
+          <p className="panel-subtitle">Stored provenance for query {queryId}.</p>
+        </div>
+        {error ? (
+          <p style={{ color: "tomato", fontSize: "0.9rem" }}>{error}</p>
+        ) : (
+          <ResultList results={results} onInspect={setSelectedResult} />
+        )}
+      </section>
+
+      {selectedResult && <ProvenancePanel result={selectedResult} onClose={() => setSelectedResult(null)} />}
+    </div>
+  );
+}
@@ -38,3 +38,121 @@ export interface IngestResponse {
   job_id: string;
   stats: IngestStats;
 }
+
+export interface QueryFilters {
+  source_ids?: string[] | null;
+  document_ids?: string[] | null;
+  tags?: string[] | null;
+}
+
+export interface BackendProfile {
+  name: string;
+  base_url: string;
+  filters: QueryFilters;
+  tls: TlsSettings;
+}
+
+export interface TlsSettings {
+  ca_bundle?: string | null;
+  pinned_certs: string[];
+}
+
+export interface ScheduleRule {
+  source_id?: string | null;
+  schedule: string;
+}
+
+export interface ScheduleSettings {
+  rules: ScheduleRule[];
+  quiet_hours?: { start: string; end: string } | null;
+  require_ac_power: boolean;
+  require_idle_minutes: number;
+}
+
+export interface DesktopSettings {
+  profiles: BackendProfile[];
+  active_profile: string;
+  auto_ingest_minutes: number;
+  schedule: ScheduleSettings;
+  notifications: {
+    ingest_finished: boolean;
+    ingest_failed: boolean;
+  };
+  query: {
+    k: number;
+    rerank?: boolean | null;
+    hybrid?: boolean | null;
+  };
+  quick_search: {
+    shortcut: string;
+    enter_action: "open" | "copy";
+  };
+  clippings: {
+    shortcut: string;
+    folder?: string | null;
+  };
+  sidecar: {
+    enabled: boolean;
+    command: string;
+    port: number;
+    db_path?: string | null;
+  };
+  network: {
+    connect_timeout_secs: number;
+    health_timeout_secs: number;
+    request_timeout_secs: number;
+    ingest_timeout_secs: number;
+    get_retries: number;
+  };
+  watcher: {
+    enabled: boolean;
+    debounce_ms: number;
+  };
+}
+
+export interface SettingsView {
+  settings: DesktopSettings;
+  effective: DesktopSettings;
+  /** Profiles with a stored token; the tokens themselves stay in the shell. */
+  token_set: string[];
+  overridden: string[];
+  backend_url: string;
+  path: string;
+}
+
+export interface DroppedFile {
+  path: string;
+  status: "processed" | "skipped" | "error" | "unsupported" | string;
+  detail?: string | null;
+}
+
+export interface DropResults {
+  files: DroppedFile[];
+  error?: string | null;
+}
+
+export interface PendingOperation {
+  id: string;
+  label: string;
+  profile: string;
+  method: string;
+  path: string;
+  queued_at: string;
+}
+
+export interface IngestJob {
+  id: string;
+  label: string;
+  sources: string[];
+  path_count: number;
+  status: "running" | "queued" | "completed" | "failed";
+  started_at: string;
+  finished_at?: string | null;
+  stats: IngestStats;
+  failed_files: { path: string; detail?: string | null }[];
+  error?: string | null;
+  backend_job_ids: string[];
+  queued_operations: string[];
+  steps_done: number;
+  steps_total: number;
+}